@@ -0,0 +1,209 @@
+use crate::options::is_rtl_char;
+
+/// 文字の方向の種類。Unicode双方向アルゴリズムの分類を簡略化したもの。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BidiClass {
+    /// 左から右へ書く文字。
+    Left,
+    /// 右から左へ書く文字。
+    Right,
+    /// 数字。
+    Number,
+    /// 数字の間に置く区切り (`.`、`,`など)。
+    Separator,
+    /// 数字に付く記号 (`%`、`$`など)。
+    Terminator,
+    /// 空白や括弧などの、方向を持たない文字。
+    Neutral,
+}
+
+/// `text`の文字を、表示する順 (左から右) に並べ替える。
+///
+/// 埋め込みの無い1つの段落として、Unicode双方向アルゴリズム (UAX #9) を簡略化して適用する。
+/// 左から右へ書く部分と数字は順番を保ち、右から左へ書く部分の括弧は左右を反転する。
+pub(crate) fn reorder_visually(text: &str, is_rtl: bool) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let paragraph = if is_rtl {
+        BidiClass::Right
+    } else {
+        BidiClass::Left
+    };
+    let classes = resolve_classes(&chars, paragraph);
+
+    // 段落の方向を基準に、各文字の階層を決める
+    let base_level = is_rtl as u8;
+    let levels: Vec<u8> = classes
+        .iter()
+        .map(|class| match (class, is_rtl) {
+            (BidiClass::Left, true) => base_level + 1,
+            (BidiClass::Right, false) => base_level + 1,
+            (BidiClass::Number, true) => base_level + 1,
+            (BidiClass::Number, false) => base_level + 2,
+            _ => base_level,
+        })
+        .collect();
+
+    // 高い階層から順に、その階層以上の連続した範囲を反転する
+    let mut order: Vec<usize> = (0..chars.len()).collect();
+    let max_level = levels.iter().copied().max().unwrap_or(0);
+    for level in (1..=max_level).rev() {
+        let mut start = 0;
+        while start < order.len() {
+            if levels[order[start]] < level {
+                start += 1;
+                continue;
+            }
+            let end = (start..order.len())
+                .find(|i| levels[order[*i]] < level)
+                .unwrap_or(order.len());
+            order[start..end].reverse();
+            start = end;
+        }
+    }
+
+    order
+        .into_iter()
+        .map(|i| {
+            if levels[i] % 2 == 1 {
+                mirror_char(chars[i])
+            } else {
+                chars[i]
+            }
+        })
+        .collect()
+}
+
+/// 文字を分類し、数字の前後の記号と、方向を持たない文字の方向を決める。
+///
+/// 結果は`Left`、`Right`、`Number`のいずれかになる。
+fn resolve_classes(chars: &[char], paragraph: BidiClass) -> Vec<BidiClass> {
+    let mut classes: Vec<BidiClass> = chars.iter().map(|c| classify(*c)).collect();
+    let len = classes.len();
+
+    // 数字の間の1つの区切りは、数字とする
+    for i in 1..len.saturating_sub(1) {
+        if classes[i] == BidiClass::Separator
+            && classes[i - 1] == BidiClass::Number
+            && classes[i + 1] == BidiClass::Number
+        {
+            classes[i] = BidiClass::Number;
+        }
+    }
+    // 数字に続く記号と、数字の前の記号は、数字とする
+    for i in 1..len {
+        if classes[i] == BidiClass::Terminator && classes[i - 1] == BidiClass::Number {
+            classes[i] = BidiClass::Number;
+        }
+    }
+    for i in (0..len.saturating_sub(1)).rev() {
+        if classes[i] == BidiClass::Terminator && classes[i + 1] == BidiClass::Number {
+            classes[i] = BidiClass::Number;
+        }
+    }
+    // 左から右へ書く文字に続く数字は、左から右へ書く
+    let mut last_strong = paragraph;
+    for class in classes.iter_mut() {
+        match class {
+            BidiClass::Left | BidiClass::Right => last_strong = *class,
+            BidiClass::Number if last_strong == BidiClass::Left => *class = BidiClass::Left,
+            _ => {}
+        }
+    }
+
+    // 方向を持たない文字は、前後の方向が同じならその方向、異なれば段落の方向とする。
+    // 数字は、右から左へ書く文字とみなす。
+    let direction = |class: BidiClass| match class {
+        BidiClass::Left => Some(BidiClass::Left),
+        BidiClass::Right | BidiClass::Number => Some(BidiClass::Right),
+        _ => None,
+    };
+    let mut start = 0;
+    while start < len {
+        if direction(classes[start]).is_some() {
+            start += 1;
+            continue;
+        }
+        let end = (start..len)
+            .find(|i| direction(classes[*i]).is_some())
+            .unwrap_or(len);
+        let before = start
+            .checked_sub(1)
+            .and_then(|i| direction(classes[i]))
+            .unwrap_or(paragraph);
+        let after = classes
+            .get(end)
+            .and_then(|class| direction(*class))
+            .unwrap_or(paragraph);
+        let resolved = if before == after { before } else { paragraph };
+        classes[start..end].fill(resolved);
+        start = end;
+    }
+
+    classes
+}
+
+fn classify(c: char) -> BidiClass {
+    if c.is_ascii_digit() || matches!(c, '\u{0660}'..='\u{0669}' | '０'..='９') {
+        BidiClass::Number
+    } else if is_rtl_char(c) {
+        BidiClass::Right
+    } else if c.is_alphabetic() {
+        BidiClass::Left
+    } else if matches!(c, '.' | ',' | ':' | '/' | '+' | '-') {
+        BidiClass::Separator
+    } else if matches!(c, '%' | '‰' | '$' | '¢' | '£' | '¥' | '€' | '#' | '°') {
+        BidiClass::Terminator
+    } else {
+        BidiClass::Neutral
+    }
+}
+
+/// 右から左へ書く部分で、左右を反転して表示する括弧。
+fn mirror_char(c: char) -> char {
+    match c {
+        '(' => ')',
+        ')' => '(',
+        '[' => ']',
+        ']' => '[',
+        '{' => '}',
+        '}' => '{',
+        '<' => '>',
+        '>' => '<',
+        '«' => '»',
+        '»' => '«',
+        '‹' => '›',
+        '›' => '‹',
+        '（' => '）',
+        '）' => '（',
+        '［' => '］',
+        '］' => '［',
+        '｛' => '｝',
+        '｝' => '｛',
+        c => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod reorder_visually {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            // 左から右へ書く段落は、そのまま
+            assert_eq!(reorder_visually("Red (20%)", false), "Red (20%)");
+            // 右から左へ書く文字は、逆順
+            assert_eq!(reorder_visually("ירוק", true), "קורי");
+            // 数字と記号は順番を保ち、括弧は左右を反転する
+            assert_eq!(reorder_visually("ירוק (20%)", true), "(20%) קורי");
+            assert_eq!(reorder_visually("ירוק 1,234.5", true), "1,234.5 קורי");
+            // 左から右へ書く語は、順番を保つ
+            assert_eq!(reorder_visually("שלום Red", true), "Red םולש");
+            assert_eq!(reorder_visually("אדום Red 2", true), "Red 2 םודא");
+            // 左から右へ書く段落の中の、右から左へ書く語
+            assert_eq!(reorder_visually("Red אדום!", false), "Red םודא!");
+        }
+    }
+}
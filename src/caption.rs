@@ -0,0 +1,145 @@
+use svg::node::element::{Group, Text as TextElement};
+
+use crate::color::format_rgb;
+use crate::options::{ChartText, TextAlign, TextDirection};
+use crate::text::wrap_text;
+use crate::typography::{apply_direction, apply_typography};
+
+/// 幅に合わせて行に分けた、グラフの周りの文字列。
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TextBlock<'a> {
+    text: &'a ChartText,
+    lines: Vec<String>,
+}
+
+impl TextBlock<'_> {
+    fn line_height(&self) -> f64 {
+        self.text.typography.size as f64 * self.text.typography.line_height
+    }
+
+    fn height(&self) -> f64 {
+        self.line_height() * self.lines.len() as f64
+    }
+}
+
+/// 文字列を`max_width`の幅に合わせて行に分ける。空の文字列は除く。
+pub(crate) fn layout_texts<'a>(
+    texts: impl IntoIterator<Item = &'a ChartText>,
+    max_width: f64,
+) -> Vec<TextBlock<'a>> {
+    texts
+        .into_iter()
+        .filter(|text| !text.text.is_empty())
+        .map(|text| TextBlock {
+            text,
+            lines: wrap_text(&text.text, &text.typography, max_width, None, false),
+        })
+        .collect()
+}
+
+/// 文字列を縦に並べたときの高さを計算する。
+pub(crate) fn calc_blocks_height(blocks: &[TextBlock]) -> f64 {
+    blocks.iter().map(TextBlock::height).sum()
+}
+
+/// 文字列を`top`から縦に並べる。
+///
+/// `left`と`right`は、文字列を揃える左端と右端。
+pub(crate) fn create_blocks(
+    blocks: &[TextBlock],
+    top: f64,
+    (left, right): (f64, f64),
+    is_chart_rtl: bool,
+) -> Group {
+    let mut group = Group::new();
+    let mut y = top;
+
+    for block in blocks {
+        let is_rtl = TextDirection::Auto.is_rtl(&block.text.text);
+        // 右から左へ書くグラフでは、左右を反転する
+        let is_left = match block.text.align {
+            TextAlign::Start => !is_chart_rtl,
+            TextAlign::Center => false,
+            TextAlign::End => is_chart_rtl,
+        };
+        let (x, anchor) = match block.text.align {
+            TextAlign::Center => ((left + right) * 0.5, "middle"),
+            _ if is_left => (left, if is_rtl { "end" } else { "start" }),
+            _ => (right, if is_rtl { "start" } else { "end" }),
+        };
+
+        let line_height = block.line_height();
+        for line in block.lines.iter() {
+            let element = TextElement::new(line.as_str())
+                .set("x", x)
+                .set("y", y + line_height * 0.5)
+                .set("text-anchor", anchor)
+                .set("fill", format_rgb(block.text.color))
+                .set("dominant-baseline", "central");
+            let element = apply_typography(element, &block.text.typography);
+            group = group.add(apply_direction(element, is_rtl, is_chart_rtl));
+
+            y += line_height;
+        }
+    }
+
+    group
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::Typography;
+
+    mod layout_texts {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            let title = ChartText {
+                text: "Sales by region".to_string(),
+                typography: Typography {
+                    size: 10,
+                    line_height: 1.5,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let empty = ChartText::default();
+            let blocks = layout_texts([&title, &empty], 60.0);
+            assert_eq!(blocks.len(), 1);
+            assert_eq!(blocks[0].lines, vec!["Sales by", "region"]);
+            assert_eq!(calc_blocks_height(&blocks), 30.0);
+        }
+    }
+
+    mod create_blocks {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            let texts = [
+                ChartText {
+                    text: "Title".to_string(),
+                    align: TextAlign::Start,
+                    ..Default::default()
+                },
+                ChartText {
+                    text: "Subtitle".to_string(),
+                    align: TextAlign::End,
+                    ..Default::default()
+                },
+            ];
+            let blocks = layout_texts(&texts, 100.0);
+
+            let text = create_blocks(&blocks, 10.0, (4.0, 96.0), false).to_string();
+            assert!(text.contains(r#"text-anchor="start" x="4" y="16">"#));
+            assert!(text.contains(r#"text-anchor="end" x="96" y="28">"#));
+
+            // 右から左へ書くグラフでは、左右を反転する
+            let text = create_blocks(&blocks, 10.0, (4.0, 96.0), true).to_string();
+            assert!(text.contains(r#"text-anchor="end" unicode-bidi="embed" x="96" y="16">"#));
+            assert!(text.contains(r#"text-anchor="start" unicode-bidi="embed" x="4" y="28">"#));
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use crate::options::CategoryColors;
+
+/// 色の無いカテゴリーの色。
+const FALLBACK_COLOR: &str = "#999";
+
+/// 各カテゴリーに、最初に現れた順に色を割り当てる。
+///
+/// 重複の無いカテゴリーと色の組を、最初に現れた順に返す。
+pub(crate) fn assign_colors<'a>(
+    categories: impl IntoIterator<Item = &'a str>,
+    colors: &CategoryColors,
+) -> Vec<(String, String)> {
+    let mut assigned: Vec<(String, String)> = Vec::new();
+    let mut palette_index = 0;
+
+    for category in categories {
+        if assigned.iter().any(|(assigned, _)| assigned == category) {
+            continue;
+        }
+        let color = match colors.colors.iter().find(|(name, _)| name == category) {
+            Some((_, color)) => color.clone(),
+            None => {
+                let color = match colors.palette.len() {
+                    0 => FALLBACK_COLOR.to_string(),
+                    len => colors.palette[palette_index % len].clone(),
+                };
+                palette_index += 1;
+                color
+            }
+        };
+        assigned.push((category.to_string(), color));
+    }
+
+    assigned
+}
+
+/// 割り当てた色から、カテゴリーの色を探す。
+pub(crate) fn find_color<'a>(assigned: &'a [(String, String)], category: &str) -> &'a str {
+    assigned
+        .iter()
+        .find(|(name, _)| name == category)
+        .map_or(FALLBACK_COLOR, |(_, color)| color.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod assign_colors {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            let colors = CategoryColors {
+                colors: vec![("B".to_string(), "#000".to_string())],
+                palette: vec!["#111".to_string(), "#222".to_string()],
+            };
+            let assigned = assign_colors(["A", "B", "C", "A", "D"], &colors);
+            assert_eq!(
+                assigned,
+                vec![
+                    ("A".to_string(), "#111".to_string()),
+                    ("B".to_string(), "#000".to_string()),
+                    ("C".to_string(), "#222".to_string()),
+                    // パレットは繰り返す
+                    ("D".to_string(), "#111".to_string()),
+                ]
+            );
+
+            assert_eq!(find_color(&assigned, "C"), "#222");
+            assert_eq!(find_color(&assigned, "E"), "#999");
+        }
+    }
+}
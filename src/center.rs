@@ -0,0 +1,181 @@
+use svg::node::element::{Group, Text as TextElement};
+
+use crate::color::format_rgb;
+use crate::options::{CenterContent, CenterLine, CenterValue, TextDirection, Typography};
+use crate::text::measure_text_width;
+use crate::typography::{apply_direction, apply_typography};
+use crate::util::Coord;
+
+/// グラフの中央の内容を作成する。
+///
+/// `total`が無い場合は、`ratio_sum`を百分率で表示する。
+pub(crate) fn create_center(
+    circle_center: Coord,
+    inner_radius: u32,
+    content: &CenterContent,
+    total: Option<f64>,
+    ratio_sum: f64,
+    is_chart_rtl: bool,
+) -> Group {
+    let lines: Vec<(&CenterLine, String)> = std::iter::once(&content.primary)
+        .chain(content.secondary.as_ref())
+        .map(|line| (line, format_value(&line.value, total, ratio_sum)))
+        .collect();
+
+    let scale = if content.auto_scale && inner_radius > 0 {
+        calc_scale(&lines, inner_radius as f64)
+    } else {
+        1.0
+    };
+    let lines: Vec<(&CenterLine, String, Typography)> = lines
+        .into_iter()
+        .map(|(line, text)| {
+            let typography = Typography {
+                size: ((line.typography.size as f64 * scale).floor() as u32).max(1),
+                ..line.typography.clone()
+            };
+            (line, text, typography)
+        })
+        .collect();
+
+    // 全体の高さの中央を、円の中心に合わせる
+    let height: f64 = lines
+        .iter()
+        .map(|(_, _, typography)| line_height(typography))
+        .sum();
+    let mut y = circle_center.1 as f64 - height * 0.5;
+
+    let mut group = Group::new();
+    for (line, text, typography) in lines {
+        let half_height = line_height(&typography) * 0.5;
+        y += half_height;
+
+        let is_rtl = TextDirection::Auto.is_rtl(&text);
+        let element = TextElement::new(text)
+            .set("x", circle_center.0)
+            .set("y", y)
+            .set("text-anchor", "middle")
+            .set("fill", format_rgb(line.color));
+        let element = apply_typography(element, &typography).set("dominant-baseline", "central");
+        group = group.add(apply_direction(element, is_rtl, is_chart_rtl));
+
+        y += half_height;
+    }
+
+    group
+}
+
+/// 値を文字列にする。
+pub(crate) fn format_value(value: &CenterValue, total: Option<f64>, ratio_sum: f64) -> String {
+    match value {
+        CenterValue::Text(text) => text.clone(),
+        CenterValue::Total(format) => match total {
+            Some(total) => format.format(total),
+            None => format.to_percentage().format(ratio_sum * 100.0),
+        },
+    }
+}
+
+/// 全ての行を囲む四角形が、半径`radius`の円に収まる倍率を計算する。
+fn calc_scale(lines: &[(&CenterLine, String)], radius: f64) -> f64 {
+    let width = lines
+        .iter()
+        .map(|(line, text)| measure_text_width(text, &line.typography))
+        .fold(0.0, f64::max);
+    let height: f64 = lines
+        .iter()
+        .map(|(line, _)| line_height(&line.typography))
+        .sum();
+    let diagonal = (width * 0.5).hypot(height * 0.5);
+
+    if diagonal > radius {
+        radius / diagonal
+    } else {
+        1.0
+    }
+}
+
+fn line_height(typography: &Typography) -> f64 {
+    typography.size as f64 * typography.line_height
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::NumberFormat;
+
+    mod create_center {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            let content = CenterContent {
+                primary: CenterLine {
+                    value: CenterValue::Total(NumberFormat::default()),
+                    ..Default::default()
+                },
+                secondary: Some(CenterLine {
+                    value: CenterValue::Text("users".to_string()),
+                    typography: Typography {
+                        size: 8,
+                        ..Default::default()
+                    },
+                    color: (128, 128, 128),
+                }),
+                auto_scale: true,
+            };
+            let text = create_center((50, 50), 40, &content, Some(1234.0), 1.0, false).to_string();
+            // 行の高さは19.2と9.6
+            assert!(text.contains(r#"font-size="16" text-anchor="middle" x="50" y="45.2">"#));
+            assert!(text.contains("1,234"));
+            assert!(text.contains(r#"fill="rgb(128, 128, 128)""#));
+            assert!(text.contains(r#"font-size="8" text-anchor="middle" x="50" y="59.6">"#));
+
+            // 穴に収まるよう縮小する
+            let text = create_center((50, 50), 10, &content, Some(1234.0), 1.0, false).to_string();
+            assert!(text.contains(r#"font-size="5""#));
+            assert!(text.contains(r#"font-size="2""#));
+        }
+    }
+
+    mod format_value {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            let text = CenterValue::Text("Total".to_string());
+            assert_eq!(format_value(&text, Some(10.0), 1.0), "Total");
+
+            let total = CenterValue::Total(NumberFormat {
+                prefix: "$".to_string(),
+                suffix: " USD".to_string(),
+                ..Default::default()
+            });
+            assert_eq!(format_value(&total, Some(1234.0), 1.0), "$1,234 USD");
+            assert_eq!(format_value(&total, Some(-5.0), 1.0), "-$5 USD");
+            // 合計が無い場合は、接頭辞と接尾辞の無い百分率
+            assert_eq!(format_value(&total, None, 0.995), "100%");
+        }
+    }
+
+    mod calc_scale {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            let line = CenterLine {
+                typography: Typography {
+                    size: 10,
+                    line_height: 1.0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            // 幅30、高さ10
+            let lines = [(&line, "abcd…".to_string())];
+            assert_eq!(calc_scale(&lines, 100.0), 1.0);
+            let diagonal = 15.0f64.hypot(5.0);
+            assert_eq!(calc_scale(&lines, 10.0), 10.0 / diagonal);
+        }
+    }
+}
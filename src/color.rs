@@ -0,0 +1,362 @@
+pub(crate) type Rgb = (u8, u8, u8);
+
+pub(crate) const BLACK: Rgb = (0, 0, 0);
+pub(crate) const WHITE: Rgb = (255, 255, 255);
+
+/// `rgb(r, g, b)`の形式の文字列にする。
+pub(crate) fn format_rgb((r, g, b): Rgb) -> String {
+    format!("rgb({r}, {g}, {b})")
+}
+
+/// CSSの色を解釈する。
+///
+/// `#rgb`、`#rgba`、`#rrggbb`、`#rrggbbaa`、`rgb()`、`rgba()`、`hsl()`、`hsla()`と色名に対応する。
+/// 透明度は無視する。
+pub(crate) fn parse_css_color(color: &str) -> Option<Rgb> {
+    let color = color.trim().to_ascii_lowercase();
+
+    if let Some(hex) = color.strip_prefix('#') {
+        return parse_hex(hex);
+    }
+
+    if let Some(args) = strip_function(&color, "rgba").or_else(|| strip_function(&color, "rgb")) {
+        return parse_rgb_function(args);
+    }
+
+    if let Some(args) = strip_function(&color, "hsla").or_else(|| strip_function(&color, "hsl")) {
+        return parse_hsl_function(args);
+    }
+
+    NAMED_COLORS
+        .iter()
+        .find(|(name, _)| *name == color)
+        .map(|(_, rgb)| *rgb)
+}
+
+fn parse_hex(hex: &str) -> Option<Rgb> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    match hex.len() {
+        3 | 4 => {
+            let digit = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).ok().map(|v| v * 17);
+            Some((digit(0)?, digit(1)?, digit(2)?))
+        }
+        6 | 8 => {
+            let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+            Some((byte(0)?, byte(2)?, byte(4)?))
+        }
+        _ => None,
+    }
+}
+
+fn strip_function<'a>(color: &'a str, name: &str) -> Option<&'a str> {
+    color
+        .strip_prefix(name)?
+        .trim_start()
+        .strip_prefix('(')?
+        .strip_suffix(')')
+}
+
+fn split_arguments(args: &str) -> Vec<&str> {
+    args.split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+        .filter(|v| !v.is_empty())
+        .collect()
+}
+
+fn parse_rgb_function(args: &str) -> Option<Rgb> {
+    let args = split_arguments(args);
+    if args.len() < 3 {
+        return None;
+    }
+
+    let channel = |v: &str| -> Option<u8> {
+        let value = match v.strip_suffix('%') {
+            Some(percent) => percent.parse::<f64>().ok()? / 100.0 * 255.0,
+            None => v.parse::<f64>().ok()?,
+        };
+        Some(value.round().clamp(0.0, 255.0) as u8)
+    };
+
+    Some((channel(args[0])?, channel(args[1])?, channel(args[2])?))
+}
+
+fn parse_hsl_function(args: &str) -> Option<Rgb> {
+    let args = split_arguments(args);
+    if args.len() < 3 {
+        return None;
+    }
+
+    let hue = args[0].trim_end_matches("deg").parse::<f64>().ok()?;
+    let saturation = args[1].strip_suffix('%')?.parse::<f64>().ok()? / 100.0;
+    let lightness = args[2].strip_suffix('%')?.parse::<f64>().ok()? / 100.0;
+
+    Some(hsl_to_rgb(
+        hue,
+        saturation.clamp(0.0, 1.0),
+        lightness.clamp(0.0, 1.0),
+    ))
+}
+
+fn hsl_to_rgb(hue: f64, saturation: f64, lightness: f64) -> Rgb {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let hue = hue.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+    let (r, g, b) = match hue as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = lightness - chroma * 0.5;
+    let to_u8 = |v: f64| ((v + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+
+    (to_u8(r), to_u8(g), to_u8(b))
+}
+
+/// WCAGの相対輝度を計算する。
+pub(crate) fn relative_luminance((r, g, b): Rgb) -> f64 {
+    let linear = |v: u8| {
+        let v = v as f64 / u8::MAX as f64;
+        if v <= 0.04045 {
+            v / 12.92
+        } else {
+            ((v + 0.055) / 1.055).powf(2.4)
+        }
+    };
+
+    0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+}
+
+/// WCAGのコントラスト比を計算する。
+pub(crate) fn contrast_ratio(lhs: Rgb, rhs: Rgb) -> f64 {
+    let lhs = relative_luminance(lhs);
+    let rhs = relative_luminance(rhs);
+    let (lighter, darker) = if lhs > rhs { (lhs, rhs) } else { (rhs, lhs) };
+
+    (lighter + 0.05) / (darker + 0.05)
+}
+
+/// 黒と白のうち、`color`とのコントラストが大きい方を返す。
+pub(crate) fn contrasting_color(color: Rgb) -> Rgb {
+    if contrast_ratio(color, BLACK) >= contrast_ratio(color, WHITE) {
+        BLACK
+    } else {
+        WHITE
+    }
+}
+
+const NAMED_COLORS: [(&str, Rgb); 148] = [
+    ("aliceblue", (240, 248, 255)),
+    ("antiquewhite", (250, 235, 215)),
+    ("aqua", (0, 255, 255)),
+    ("aquamarine", (127, 255, 212)),
+    ("azure", (240, 255, 255)),
+    ("beige", (245, 245, 220)),
+    ("bisque", (255, 228, 196)),
+    ("black", (0, 0, 0)),
+    ("blanchedalmond", (255, 235, 205)),
+    ("blue", (0, 0, 255)),
+    ("blueviolet", (138, 43, 226)),
+    ("brown", (165, 42, 42)),
+    ("burlywood", (222, 184, 135)),
+    ("cadetblue", (95, 158, 160)),
+    ("chartreuse", (127, 255, 0)),
+    ("chocolate", (210, 105, 30)),
+    ("coral", (255, 127, 80)),
+    ("cornflowerblue", (100, 149, 237)),
+    ("cornsilk", (255, 248, 220)),
+    ("crimson", (220, 20, 60)),
+    ("cyan", (0, 255, 255)),
+    ("darkblue", (0, 0, 139)),
+    ("darkcyan", (0, 139, 139)),
+    ("darkgoldenrod", (184, 134, 11)),
+    ("darkgray", (169, 169, 169)),
+    ("darkgreen", (0, 100, 0)),
+    ("darkgrey", (169, 169, 169)),
+    ("darkkhaki", (189, 183, 107)),
+    ("darkmagenta", (139, 0, 139)),
+    ("darkolivegreen", (85, 107, 47)),
+    ("darkorange", (255, 140, 0)),
+    ("darkorchid", (153, 50, 204)),
+    ("darkred", (139, 0, 0)),
+    ("darksalmon", (233, 150, 122)),
+    ("darkseagreen", (143, 188, 143)),
+    ("darkslateblue", (72, 61, 139)),
+    ("darkslategray", (47, 79, 79)),
+    ("darkslategrey", (47, 79, 79)),
+    ("darkturquoise", (0, 206, 209)),
+    ("darkviolet", (148, 0, 211)),
+    ("deeppink", (255, 20, 147)),
+    ("deepskyblue", (0, 191, 255)),
+    ("dimgray", (105, 105, 105)),
+    ("dimgrey", (105, 105, 105)),
+    ("dodgerblue", (30, 144, 255)),
+    ("firebrick", (178, 34, 34)),
+    ("floralwhite", (255, 250, 240)),
+    ("forestgreen", (34, 139, 34)),
+    ("fuchsia", (255, 0, 255)),
+    ("gainsboro", (220, 220, 220)),
+    ("ghostwhite", (248, 248, 255)),
+    ("gold", (255, 215, 0)),
+    ("goldenrod", (218, 165, 32)),
+    ("gray", (128, 128, 128)),
+    ("green", (0, 128, 0)),
+    ("greenyellow", (173, 255, 47)),
+    ("grey", (128, 128, 128)),
+    ("honeydew", (240, 255, 240)),
+    ("hotpink", (255, 105, 180)),
+    ("indianred", (205, 92, 92)),
+    ("indigo", (75, 0, 130)),
+    ("ivory", (255, 255, 240)),
+    ("khaki", (240, 230, 140)),
+    ("lavender", (230, 230, 250)),
+    ("lavenderblush", (255, 240, 245)),
+    ("lawngreen", (124, 252, 0)),
+    ("lemonchiffon", (255, 250, 205)),
+    ("lightblue", (173, 216, 230)),
+    ("lightcoral", (240, 128, 128)),
+    ("lightcyan", (224, 255, 255)),
+    ("lightgoldenrodyellow", (250, 250, 210)),
+    ("lightgray", (211, 211, 211)),
+    ("lightgreen", (144, 238, 144)),
+    ("lightgrey", (211, 211, 211)),
+    ("lightpink", (255, 182, 193)),
+    ("lightsalmon", (255, 160, 122)),
+    ("lightseagreen", (32, 178, 170)),
+    ("lightskyblue", (135, 206, 250)),
+    ("lightslategray", (119, 136, 153)),
+    ("lightslategrey", (119, 136, 153)),
+    ("lightsteelblue", (176, 196, 222)),
+    ("lightyellow", (255, 255, 224)),
+    ("lime", (0, 255, 0)),
+    ("limegreen", (50, 205, 50)),
+    ("linen", (250, 240, 230)),
+    ("magenta", (255, 0, 255)),
+    ("maroon", (128, 0, 0)),
+    ("mediumaquamarine", (102, 205, 170)),
+    ("mediumblue", (0, 0, 205)),
+    ("mediumorchid", (186, 85, 211)),
+    ("mediumpurple", (147, 112, 219)),
+    ("mediumseagreen", (60, 179, 113)),
+    ("mediumslateblue", (123, 104, 238)),
+    ("mediumspringgreen", (0, 250, 154)),
+    ("mediumturquoise", (72, 209, 204)),
+    ("mediumvioletred", (199, 21, 133)),
+    ("midnightblue", (25, 25, 112)),
+    ("mintcream", (245, 255, 250)),
+    ("mistyrose", (255, 228, 225)),
+    ("moccasin", (255, 228, 181)),
+    ("navajowhite", (255, 222, 173)),
+    ("navy", (0, 0, 128)),
+    ("oldlace", (253, 245, 230)),
+    ("olive", (128, 128, 0)),
+    ("olivedrab", (107, 142, 35)),
+    ("orange", (255, 165, 0)),
+    ("orangered", (255, 69, 0)),
+    ("orchid", (218, 112, 214)),
+    ("palegoldenrod", (238, 232, 170)),
+    ("palegreen", (152, 251, 152)),
+    ("paleturquoise", (175, 238, 238)),
+    ("palevioletred", (219, 112, 147)),
+    ("papayawhip", (255, 239, 213)),
+    ("peachpuff", (255, 218, 185)),
+    ("peru", (205, 133, 63)),
+    ("pink", (255, 192, 203)),
+    ("plum", (221, 160, 221)),
+    ("powderblue", (176, 224, 230)),
+    ("purple", (128, 0, 128)),
+    ("rebeccapurple", (102, 51, 153)),
+    ("red", (255, 0, 0)),
+    ("rosybrown", (188, 143, 143)),
+    ("royalblue", (65, 105, 225)),
+    ("saddlebrown", (139, 69, 19)),
+    ("salmon", (250, 128, 114)),
+    ("sandybrown", (244, 164, 96)),
+    ("seagreen", (46, 139, 87)),
+    ("seashell", (255, 245, 238)),
+    ("sienna", (160, 82, 45)),
+    ("silver", (192, 192, 192)),
+    ("skyblue", (135, 206, 235)),
+    ("slateblue", (106, 90, 205)),
+    ("slategray", (112, 128, 144)),
+    ("slategrey", (112, 128, 144)),
+    ("snow", (255, 250, 250)),
+    ("springgreen", (0, 255, 127)),
+    ("steelblue", (70, 130, 180)),
+    ("tan", (210, 180, 140)),
+    ("teal", (0, 128, 128)),
+    ("thistle", (216, 191, 216)),
+    ("tomato", (255, 99, 71)),
+    ("turquoise", (64, 224, 208)),
+    ("violet", (238, 130, 238)),
+    ("wheat", (245, 222, 179)),
+    ("white", (255, 255, 255)),
+    ("whitesmoke", (245, 245, 245)),
+    ("yellow", (255, 255, 0)),
+    ("yellowgreen", (154, 205, 50)),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod parse_css_color {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            assert_eq!(parse_css_color("#999"), Some((153, 153, 153)));
+            assert_eq!(parse_css_color("#fe5555"), Some((254, 85, 85)));
+            assert_eq!(parse_css_color("#FE555580"), Some((254, 85, 85)));
+            assert_eq!(parse_css_color("rgb(1, 2, 3)"), Some((1, 2, 3)));
+            assert_eq!(parse_css_color("rgba(1 2 3 / 0.5)"), Some((1, 2, 3)));
+            assert_eq!(parse_css_color("rgb(100%, 0%, 50%)"), Some((255, 0, 128)));
+            assert_eq!(parse_css_color("hsl(120, 100%, 50%)"), Some((0, 255, 0)));
+            assert_eq!(parse_css_color("hsl(0deg 0% 100%)"), Some((255, 255, 255)));
+            assert_eq!(parse_css_color(" Navy "), Some((0, 0, 128)));
+            assert_eq!(parse_css_color("#12"), None);
+            assert_eq!(parse_css_color("url(#gradient)"), None);
+            assert_eq!(parse_css_color("unknown"), None);
+        }
+    }
+
+    mod relative_luminance {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            assert_eq!(relative_luminance(BLACK), 0.0);
+            assert!((relative_luminance(WHITE) - 1.0).abs() < 1e-9);
+            assert!((relative_luminance((255, 0, 0)) - 0.2126).abs() < 1e-9);
+        }
+    }
+
+    mod contrast_ratio {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            assert!((contrast_ratio(BLACK, WHITE) - 21.0).abs() < 1e-9);
+            assert!((contrast_ratio(WHITE, BLACK) - 21.0).abs() < 1e-9);
+            assert_eq!(contrast_ratio((10, 20, 30), (10, 20, 30)), 1.0);
+        }
+    }
+
+    mod contrasting_color {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            assert_eq!(contrasting_color(BLACK), WHITE);
+            assert_eq!(contrasting_color(WHITE), BLACK);
+            assert_eq!(contrasting_color((0, 0, 128)), WHITE);
+            assert_eq!(contrasting_color((85, 254, 85)), BLACK);
+            // 和の比較では黒になってしまう暗めの色
+            assert_eq!(contrasting_color((51, 102, 254)), WHITE);
+        }
+    }
+}
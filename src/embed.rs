@@ -0,0 +1,127 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+use crate::error::PieChartError;
+use crate::font::Font;
+use crate::options::Typography;
+use crate::subset::subset_font;
+use crate::typography::format_font_family;
+
+/// 埋め込むフォントごとの、フォントと使われる文字列。
+pub(crate) struct FontEmbedding<'a> {
+    font: &'a Font,
+    text: String,
+    family_name: String,
+}
+
+/// 文字列と書式の組から、埋め込むフォントを集める。
+///
+/// 同じフォントは1つにまとめる。フォントの無い書式は無視する。
+/// フォント名には`id_prefix`を付ける。
+pub(crate) fn collect_fonts<'a>(
+    texts: impl IntoIterator<Item = (&'a Typography, String)>,
+    id_prefix: &str,
+) -> Vec<FontEmbedding<'a>> {
+    let mut embeddings: Vec<FontEmbedding> = Vec::new();
+    for (typography, text) in texts {
+        let Some(font) = &typography.font else {
+            continue;
+        };
+        match embeddings
+            .iter_mut()
+            .find(|embedding| embedding.font == font)
+        {
+            Some(embedding) => embedding.text.push_str(&text),
+            None => {
+                let family_name = format!("{id_prefix}svg-pie-chart-{}", embeddings.len());
+                embeddings.push(FontEmbedding {
+                    font,
+                    text,
+                    family_name,
+                });
+            }
+        }
+    }
+
+    embeddings
+}
+
+impl FontEmbedding<'_> {
+    /// 使われる文字に絞ったフォントを埋め込む`@font-face`を作成する。
+    pub(crate) fn create_font_face(&self) -> Result<String, PieChartError> {
+        let data = subset_font(self.font, &self.text)?;
+        Ok(format!(
+            "@font-face{{font-family:'{}';src:url(data:font/ttf;base64,{}) format('truetype');}}",
+            self.family_name,
+            STANDARD.encode(data)
+        ))
+    }
+
+    /// `typography`がこのフォントを使う場合、`font-family`の先頭に埋め込んだフォントを加える。
+    pub(crate) fn apply(&self, typography: &mut Typography) {
+        if typography.font.as_ref() != Some(self.font) {
+            return;
+        }
+
+        typography.font_family = if typography.font_family.trim().is_empty() {
+            format!("'{}'", self.family_name)
+        } else {
+            format!(
+                "'{}', {}",
+                self.family_name,
+                format_font_family(&typography.font_family)
+            )
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::font::tests::create_test_font;
+
+    mod font_embedding {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            let font = Font::from_bytes(create_test_font()).unwrap();
+            let mut typography = Typography {
+                font_family: "Test Font, sans-serif".to_string(),
+                font: Some(font.clone()),
+                ..Default::default()
+            };
+            let other = Typography::default();
+
+            let embeddings = collect_fonts(
+                [
+                    (&typography, "A".to_string()),
+                    (&other, "B".to_string()),
+                    (&typography, "東".to_string()),
+                ],
+                "chart-",
+            );
+            assert_eq!(embeddings.len(), 1);
+            assert_eq!(embeddings[0].text, "A東");
+
+            let font_face = embeddings[0].create_font_face().unwrap();
+            assert!(font_face.starts_with(
+                "@font-face{font-family:'chart-svg-pie-chart-0';src:url(data:font/ttf;base64,"
+            ));
+            assert!(font_face.ends_with(") format('truetype');}"));
+
+            let mut other = other;
+            let embedding = FontEmbedding {
+                font: &font,
+                text: String::new(),
+                family_name: "svg-pie-chart-0".to_string(),
+            };
+            embedding.apply(&mut typography);
+            embedding.apply(&mut other);
+            assert_eq!(
+                typography.font_family,
+                "'svg-pie-chart-0', Test Font, sans-serif"
+            );
+            assert_eq!(other.font_family, "sans-serif");
+        }
+    }
+}
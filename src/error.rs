@@ -1,4 +1,10 @@
 #[derive(Debug, Clone, PartialEq)]
 pub enum PieChartError {
     ParallelVectorsDoNotAcross,
+    FontNotReadable(String),
+    InvalidFont,
+    FontRequired,
+    FontNotSubsettable,
+    MissingGlyph(char),
+    InvalidIdPrefix(String),
 }
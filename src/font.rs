@@ -0,0 +1,289 @@
+//! Fonts loaded from local files.
+//!
+//! This module is available with the `font` feature.
+use std::{fmt, path::Path, sync::Arc};
+
+use ttf_parser::Face;
+
+use crate::error::PieChartError;
+use crate::id::fnv1a;
+use crate::text::estimate_char_width;
+
+/// A font (TrueType or OpenType) loaded from a file or bytes.
+///
+/// Used for measuring widths of texts with real glyph advances.
+/// Characters without glyphs in the font are estimated.
+///
+/// Cloning is cheap, because the data of the font is shared.
+#[derive(Clone)]
+pub struct Font {
+    data: Arc<Vec<u8>>,
+    index: u32,
+    /// データのハッシュ値。
+    ///
+    /// グラフの内容からidの接頭辞を作る際、フォントを区別するために使う。
+    digest: u64,
+}
+
+impl Font {
+    /// Loads the first face of a font from a file.
+    pub fn from_file<P>(path: P) -> Result<Font, PieChartError>
+    where
+        P: AsRef<Path>,
+    {
+        let data =
+            std::fs::read(path).map_err(|e| PieChartError::FontNotReadable(e.to_string()))?;
+        Font::from_bytes(data)
+    }
+
+    /// Loads the first face of a font from bytes.
+    pub fn from_bytes(data: Vec<u8>) -> Result<Font, PieChartError> {
+        Font::from_bytes_with_index(data, 0)
+    }
+
+    /// Loads the `index`-th face of a font collection (`.ttc`, `.otc`) from bytes.
+    pub fn from_bytes_with_index(data: Vec<u8>, index: u32) -> Result<Font, PieChartError> {
+        Face::parse(&data, index).map_err(|_| PieChartError::InvalidFont)?;
+        Ok(Font {
+            digest: fnv1a(&data),
+            data: Arc::new(data),
+            index,
+        })
+    }
+
+    /// Measures the width of `text` at font size `size`.
+    pub fn text_width(&self, text: &str, size: f64) -> f64 {
+        self.char_widths(text, size).iter().sum()
+    }
+
+    /// 文字ごとの幅を計測する。
+    pub(crate) fn char_widths(&self, text: &str, size: f64) -> Vec<f64> {
+        let face = self.face();
+        let scale = size / face.units_per_em() as f64;
+
+        text.chars()
+            .map(|c| {
+                face.glyph_index(c)
+                    .and_then(|id| face.glyph_hor_advance(id))
+                    .map(|advance| advance as f64 * scale)
+                    .unwrap_or_else(|| estimate_char_width(c, size))
+            })
+            .collect()
+    }
+
+    pub(crate) fn face(&self) -> Face<'_> {
+        // 作成時に解析できることを確認済み
+        Face::parse(&self.data, self.index).expect("the font is already validated")
+    }
+}
+
+impl fmt::Debug for Font {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Font")
+            .field("len", &self.data.len())
+            .field("index", &self.index)
+            .field("digest", &format_args!("{:016x}", self.digest))
+            .finish()
+    }
+}
+
+impl PartialEq for Font {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+            && (Arc::ptr_eq(&self.data, &other.data) || self.data == other.data)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// テスト用の小さなTrueTypeフォントを作成する。
+    ///
+    /// unitsPerEmは1000で、グリフは以下の通り。
+    ///
+    /// | ID | 文字 | 送り幅 | 形 |
+    /// |----|------|--------|----|
+    /// | 0 | .notdef | 500 | 無し |
+    /// | 1 | `A` | 600 | 四角形 |
+    /// | 2 | `東` | 1000 | 四角形 |
+    /// | 3 | ` ` | 250 | 無し |
+    /// | 4 | `B` | 600 | 1番のグリフの複合グリフ |
+    /// | 5 | `C` | 600 | 四角形 |
+    pub(crate) fn create_test_font() -> Vec<u8> {
+        let square = |x_min: i16, y_min: i16, x_max: i16, y_max: i16| {
+            let mut glyph = Vec::new();
+            for v in [1, x_min, y_min, x_max, y_max] {
+                glyph.extend(v.to_be_bytes());
+            }
+            // endPtsOfContours, instructionLength
+            glyph.extend(3u16.to_be_bytes());
+            glyph.extend(0u16.to_be_bytes());
+            // 全て曲線上の点
+            glyph.extend([1u8; 4]);
+            for x in [x_min, x_max - x_min, 0, x_min - x_max] {
+                glyph.extend(x.to_be_bytes());
+            }
+            for y in [y_min, 0, y_max - y_min, 0] {
+                glyph.extend(y.to_be_bytes());
+            }
+            glyph
+        };
+        let mut composite = Vec::new();
+        for v in [-1i16, 150, 0, 550, 700] {
+            composite.extend(v.to_be_bytes());
+        }
+        // ARG_1_AND_2_ARE_WORDS | ARGS_ARE_XY_VALUES, glyphIndex, arg1, arg2
+        for v in [0x0003u16, 1, 50, 0] {
+            composite.extend(v.to_be_bytes());
+        }
+
+        let glyphs = [
+            Vec::new(),
+            square(100, 0, 500, 700),
+            square(100, -100, 900, 800),
+            Vec::new(),
+            composite,
+            square(50, 0, 550, 700),
+        ];
+        let advances: [u16; 6] = [500, 600, 1000, 250, 600, 600];
+        let mapping: [(u32, u32); 5] = [(0x20, 3), (0x41, 1), (0x42, 4), (0x43, 5), (0x6771, 2)];
+
+        let mut glyf = Vec::new();
+        let mut loca = Vec::new();
+        for glyph in glyphs.iter() {
+            loca.extend((glyf.len() as u32).to_be_bytes());
+            glyf.extend(glyph);
+            while glyf.len() % 4 != 0 {
+                glyf.push(0);
+            }
+        }
+        loca.extend((glyf.len() as u32).to_be_bytes());
+
+        let mut head = Vec::new();
+        head.extend(0x0001_0000u32.to_be_bytes());
+        head.extend(0x0001_0000u32.to_be_bytes());
+        head.extend(0u32.to_be_bytes());
+        head.extend(0x5F0F_3CF5u32.to_be_bytes());
+        head.extend(0u16.to_be_bytes());
+        head.extend(1000u16.to_be_bytes());
+        head.extend([0u8; 16]);
+        for v in [0i16, -100, 900, 800] {
+            head.extend(v.to_be_bytes());
+        }
+        // macStyle, lowestRecPPEM, fontDirectionHint, indexToLocFormat (long), glyphDataFormat
+        for v in [0i16, 3, 2, 1, 0] {
+            head.extend(v.to_be_bytes());
+        }
+
+        let mut hhea = Vec::new();
+        hhea.extend(0x0001_0000u32.to_be_bytes());
+        for v in [800i16, -200, 0, 1000, 0, 0, 900, 1, 0, 0, 0, 0, 0, 0, 0] {
+            hhea.extend(v.to_be_bytes());
+        }
+        hhea.extend((glyphs.len() as u16).to_be_bytes());
+
+        let mut maxp = Vec::new();
+        maxp.extend(0x0000_5000u32.to_be_bytes());
+        maxp.extend((glyphs.len() as u16).to_be_bytes());
+
+        let mut hmtx = Vec::new();
+        for advance in advances {
+            hmtx.extend(advance.to_be_bytes());
+            hmtx.extend(0i16.to_be_bytes());
+        }
+
+        let mut cmap = Vec::new();
+        // version, numTables, platformID (Windows), encodingID (Unicode full), offset
+        for v in [0u16, 1, 3, 10] {
+            cmap.extend(v.to_be_bytes());
+        }
+        cmap.extend(12u32.to_be_bytes());
+        // format 12
+        cmap.extend(12u16.to_be_bytes());
+        cmap.extend(0u16.to_be_bytes());
+        cmap.extend((16 + 12 * mapping.len() as u32).to_be_bytes());
+        cmap.extend(0u32.to_be_bytes());
+        cmap.extend((mapping.len() as u32).to_be_bytes());
+        for (code, id) in mapping {
+            for v in [code, code, id] {
+                cmap.extend(v.to_be_bytes());
+            }
+        }
+
+        let tables: [(&[u8; 4], Vec<u8>); 7] = [
+            (b"cmap", cmap),
+            (b"glyf", glyf),
+            (b"head", head),
+            (b"hhea", hhea),
+            (b"hmtx", hmtx),
+            (b"loca", loca),
+            (b"maxp", maxp),
+        ];
+
+        let mut font = Vec::new();
+        font.extend(0x0001_0000u32.to_be_bytes());
+        // numTables, searchRange, entrySelector, rangeShift
+        for v in [tables.len() as u16, 64, 2, 48] {
+            font.extend(v.to_be_bytes());
+        }
+        let mut offset = 12 + 16 * tables.len();
+        let mut body = Vec::new();
+        for (tag, data) in tables.iter() {
+            font.extend(*tag);
+            font.extend(0u32.to_be_bytes());
+            font.extend((offset as u32).to_be_bytes());
+            font.extend((data.len() as u32).to_be_bytes());
+            body.extend(data);
+            while body.len() % 4 != 0 {
+                body.push(0);
+            }
+            offset = 12 + 16 * tables.len() + body.len();
+        }
+        font.extend(body);
+
+        font
+    }
+
+    mod from_bytes {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_font() {
+            assert!(Font::from_bytes(create_test_font()).is_ok());
+        }
+
+        #[test]
+        fn failed_when_invalid_font() {
+            assert_eq!(
+                Font::from_bytes(vec![0, 1, 2, 3]),
+                Err(PieChartError::InvalidFont)
+            );
+        }
+    }
+
+    mod from_file {
+        use super::*;
+
+        #[test]
+        fn failed_when_file_not_exist() {
+            let result = Font::from_file("not_exist_font_file.ttf");
+            assert!(matches!(result, Err(PieChartError::FontNotReadable(_))));
+        }
+    }
+
+    mod text_width {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            let font = Font::from_bytes(create_test_font()).unwrap();
+            assert_eq!(font.text_width("", 10.0), 0.0);
+            assert_eq!(font.text_width("A", 10.0), 6.0);
+            assert_eq!(font.text_width("A 東", 10.0), 18.5);
+            // グリフの無い文字は見積もる
+            assert_eq!(font.text_width("Z", 10.0), 6.0);
+            assert_eq!(font.text_width("京", 10.0), 10.0);
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use svg::node::element::Rectangle;
+
+use crate::color::{format_rgb, Rgb};
+use crate::layout::Area;
+use crate::options::FrameOptions;
+
+/// `area`に背景と枠を作成する。どちらも無い場合は`None`を返す。
+///
+/// 枠の線は、`area`の内側に収まるよう描く。
+pub(crate) fn create_frame(
+    area: &Area,
+    background: Option<Rgb>,
+    frame: Option<&FrameOptions>,
+) -> Option<Rectangle> {
+    if background.is_none() && frame.is_none() {
+        return None;
+    }
+
+    let stroke_width = frame.map_or(0.0, |frame| frame.width);
+    let inset = stroke_width * 0.5;
+    let mut rectangle = Rectangle::new()
+        .set("x", area.left + inset)
+        .set("y", area.top + inset)
+        .set("width", (area.width() - stroke_width).max(0.0))
+        .set("height", (area.height() - stroke_width).max(0.0))
+        .set("fill", background.map_or("none".to_string(), format_rgb));
+
+    if let Some(frame) = frame {
+        rectangle = rectangle
+            .set("stroke", format_rgb(frame.color))
+            .set("stroke-width", frame.width);
+        if frame.corner_radius > 0.0 {
+            rectangle = rectangle.set("rx", frame.corner_radius);
+        }
+    }
+
+    Some(rectangle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod create_frame {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            let area = Area::from_size(100, 80);
+            assert!(create_frame(&area, None, None).is_none());
+
+            let rectangle = create_frame(&area, Some((255, 250, 240)), None).unwrap();
+            assert_eq!(
+                rectangle.to_string(),
+                r#"<rect fill="rgb(255, 250, 240)" height="80" width="100" x="0" y="0"/>"#
+            );
+
+            // 枠の線は内側に収める
+            let frame = FrameOptions {
+                width: 2.0,
+                corner_radius: 8.0,
+                ..Default::default()
+            };
+            let rectangle = create_frame(&area, None, Some(&frame)).unwrap();
+            assert_eq!(
+                rectangle.to_string(),
+                r#"<rect fill="none" height="78" rx="8" stroke="rgb(0, 0, 0)" stroke-width="2" width="98" x="1" y="1"/>"#
+            );
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use crate::error::PieChartError;
+
+/// FNV-1aのオフセット基底。
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+/// FNV-1aの素数。
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// グラフの内容から、idの接頭辞を作成する。
+///
+/// 同じ内容のグラフは同じ接頭辞になる。
+/// 重複したidの定義はバイト単位で同じなため、同じ描画になる。
+/// ただし、1つのHTML文書の中ではidの重複自体が不正になるため、
+/// 同じグラフを並べる場合は、呼び出し側で接頭辞を指定する必要がある。
+pub(crate) fn create_id_prefix(content: &str) -> String {
+    format!("pie-{:08x}-", fold_hash(fnv1a(content.as_bytes())))
+}
+
+/// 指定されたidの接頭辞を検証する。
+///
+/// `url(#…)`や属性値の中で壊れないよう、XMLの名前に使える文字のうち、
+/// 英数字と`-`、`_`、`.`だけを許す。
+/// 空の接頭辞は許すが、先頭は数字や`-`、`.`にできない。
+pub(crate) fn validate_id_prefix(prefix: &str) -> Result<(), PieChartError> {
+    let is_valid_start = prefix
+        .chars()
+        .next()
+        .is_none_or(|c| c.is_alphabetic() || c == '_');
+    let is_valid_chars = prefix
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if is_valid_start && is_valid_chars {
+        Ok(())
+    } else {
+        Err(PieChartError::InvalidIdPrefix(prefix.to_string()))
+    }
+}
+
+/// FNV-1aでハッシュ値を計算する。
+///
+/// 実行環境によらず同じ値になるよう、標準ライブラリのハッシュは使わない。
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(FNV_OFFSET_BASIS, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// 64ビットのハッシュ値を32ビットに畳む。
+fn fold_hash(hash: u64) -> u32 {
+    (hash >> 32) as u32 ^ hash as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod create_id_prefix {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            assert_eq!(create_id_prefix("a"), create_id_prefix("a"));
+            assert_ne!(create_id_prefix("a"), create_id_prefix("b"));
+            assert!(create_id_prefix("a").starts_with("pie-"));
+        }
+    }
+
+    mod validate_id_prefix {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_prefix() {
+            for prefix in ["", "sales-", "_chart.1-", "売上-"] {
+                assert_eq!(validate_id_prefix(prefix), Ok(()));
+            }
+        }
+
+        #[test]
+        fn failure_when_breaking_reference() {
+            for prefix in ["a b", "a)", "a(", "a#", "a\"", "a'", "a;", "1a", "-a"] {
+                assert_eq!(
+                    validate_id_prefix(prefix),
+                    Err(PieChartError::InvalidIdPrefix(prefix.to_string()))
+                );
+            }
+        }
+    }
+
+    mod fnv1a {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+            assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+            assert_eq!(fnv1a(b"foobar"), 0x8594_4171_f739_67e8);
+        }
+    }
+}
@@ -1,26 +1,53 @@
-use svg::node::element::{Group, Text as TextElement};
+use std::f64::consts::{PI, TAU};
 
-use crate::util::{calc_angle_coord, calc_point, normalize_angle};
+use svg::node::element::{
+    path::Data, Definitions, Group, Path, TSpan, Text as TextElement, TextPath, Title,
+};
 
-pub(crate) fn crate_label<S>(
-    circle_center: (u32, u32),
-    color: (u8, u8, u8),
-    font_family: S,
-    size: u32,
-    position_radius: u32,
+#[cfg(feature = "font")]
+use crate::bidi::reorder_visually;
+use crate::color::{contrasting_color, format_rgb, parse_css_color, Rgb, BLACK};
+use crate::error::PieChartError;
+use crate::options::{
+    LabelColor, LabelHalo, LabelOptions, LabelOrientation, LabelOverflow, Typography,
+};
+#[cfg(feature = "font")]
+use crate::outline::{create_outline_data, TextPlacement};
+use crate::ruby::{layout_ruby, parse_ruby};
+use crate::text::{
+    calc_baseline_shift, ellipsize, measure_text_length, measure_vertical_metrics,
+    split_upright_digits, wrap_text,
+};
+use crate::typography::{apply_direction, apply_typography};
+use crate::util::{calc_angle_coord, calc_point, normalize_angle, Coord};
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn crate_label(
+    circle_center: Coord,
+    options: &LabelOptions,
     center_angle: f64,
-    _target_angle_range: f64,
+    target_angle_range: f64,
     label: &str,
-) -> Group
-where
-    S: AsRef<str>,
-{
-    let base_color = format!("rgb({}, {}, {})", color.0, color.1, color.2);
-    let color_total = color.0 as u32 + color.1 as u32 + color.2 as u32;
-    let invert_color = if color_total > (u8::MAX / 2) as u32 {
-        "#000"
-    } else {
-        "#fff"
+    pie_color: &str,
+    label_path_id: &str,
+    is_chart_rtl: bool,
+) -> Result<Group, PieChartError> {
+    // 閾値より小さい扇形の場合、空のGroupを返す
+    if options
+        .hide_below
+        .is_some_and(|threshold| threshold.is_below(target_angle_range))
+    {
+        return Ok(Group::new());
+    }
+
+    // 円の半径からの位置は、呼び出し元で解決しておく
+    let position_radius = options.position_radius.unwrap_or_default();
+
+    let base_color = match options.color {
+        LabelColor::Fixed(color) => color,
+        LabelColor::Auto => parse_css_color(pie_color)
+            .map(contrasting_color)
+            .unwrap_or(BLACK),
     };
 
     let center_angle = normalize_angle(center_angle);
@@ -31,31 +58,519 @@ where
         circle_center.1,
         position_radius,
     );
+    let rotation = calc_rotation(options.orientation, center_angle);
+
+    // ルビの記法を解析し、親文字だけの文字列にする
+    let (label, rubies) = match options.ruby {
+        Some(_) => parse_ruby(label),
+        None => (label.to_string(), Vec::new()),
+    };
+    let label = label.as_str();
+    let is_rtl = options.direction.is_rtl(label);
 
-    let text_base = TextElement::new(label)
-        .set("font-size", size)
-        .set("x", center_angle_point.0)
-        .set("y", center_angle_point.1)
-        .set("text-anchor", "middle");
-
-    // let text_node = TextNode::new(label);
-    // let text_base = TextElement::new()
-    //     .set("font-size", size)
-    //     .set("x", center_angle_point.0)
-    //     .set("y", center_angle_point.1)
-    //     .set("text-anchor", "middle")
-    //     .add(text_node);
-
-    let text_base = if !font_family.as_ref().is_empty() {
-        text_base.set("font-family", format!("'{}'", font_family.as_ref()))
+    let arc_length = (options.orientation == LabelOrientation::Curved)
+        .then(|| position_radius as f64 * target_angle_range.abs().min(TAU));
+    let Some(lines) = split_lines(label, options, arc_length) else {
+        return Ok(Group::new());
+    };
+    let is_altered = lines.len() != 1 || lines[0] != label;
+
+    let default_baseline = if options.orientation == LabelOrientation::Horizontal {
+        "auto"
     } else {
-        text_base
+        "central"
     };
+    let dominant_baseline = options
+        .typography
+        .dominant_baseline
+        .as_deref()
+        .unwrap_or(default_baseline);
+
+    let ruby_typography = options
+        .ruby
+        .filter(|_| !rubies.is_empty() && options.orientation != LabelOrientation::Curved)
+        .map(|ruby| Typography {
+            size: ((options.typography.size as f64 * ruby.size_ratio).round() as u32).max(1),
+            letter_spacing: None,
+            ..options.typography.clone()
+        });
+    let ruby_size = ruby_typography
+        .as_ref()
+        .map_or(0.0, |ruby_typography| ruby_typography.size as f64);
 
-    let text_body = text_base.clone().set("fill", base_color);
-    let text_under = text_base.set("stroke", invert_color).set("stroke-width", 2);
+    // 複数行の場合、1行目の位置を中心からずらす量。
+    // ルビの分だけ行の高さを増やし、全体が中心に来るよう下 (縦書きでは左) にずらす。
+    let line_height = options.typography.size as f64 * options.typography.line_height + ruby_size;
+    let first_line_offset = -line_height * (lines.len() as f64 - 1.0) * 0.5 + ruby_size * 0.5;
+
+    // 各ルビの中心の位置 (回転前)
+    let ruby_points: Vec<(String, (f64, f64))> = match &ruby_typography {
+        Some(_) => {
+            let size = options.typography.size as f64;
+            let (ascender, descender) = measure_vertical_metrics(&options.typography);
+            // 行の位置から、親文字の中央までの距離
+            let central_shift = calc_baseline_shift(dominant_baseline, ascender, descender)
+                - calc_baseline_shift("central", ascender, descender);
+            layout_ruby(
+                label,
+                &lines,
+                &rubies,
+                &options.typography,
+                options.orientation == LabelOrientation::Vertical,
+            )
+            .into_iter()
+            .map(|(i, ruby, along)| {
+                // 右から左へ書く場合は、行の中の位置が反転する
+                let along = if is_rtl { -along } else { along };
+                let offset = first_line_offset + line_height * i as f64;
+                let point = if options.orientation == LabelOrientation::Vertical {
+                    (
+                        center_angle_point.0 - offset + (size + ruby_size) * 0.5,
+                        center_angle_point.1 + along,
+                    )
+                } else {
+                    (
+                        center_angle_point.0 + along,
+                        center_angle_point.1 + offset + central_shift - (size + ruby_size) * 0.5,
+                    )
+                };
+                (ruby, point)
+            })
+            .collect()
+        }
+        None => Vec::new(),
+    };
+
+    #[cfg(feature = "font")]
+    if options.outline {
+        let font = options
+            .typography
+            .font
+            .as_ref()
+            .ok_or(PieChartError::FontRequired)?;
+
+        let mut data: String = lines
+            .iter()
+            .enumerate()
+            .map(|(i, line)| {
+                let placement = if options.orientation == LabelOrientation::Vertical {
+                    // 右の列から左へ並べる
+                    let offset = first_line_offset + line_height * i as f64;
+                    TextPlacement::Vertical {
+                        point: (center_angle_point.0 - offset, center_angle_point.1),
+                    }
+                } else if options.orientation == LabelOrientation::Curved {
+                    let (from_angle, _, range, clockwise) =
+                        calc_arc_angles(center_angle, target_angle_range);
+                    TextPlacement::Arc {
+                        center: (circle_center.0 as f64, circle_center.1 as f64),
+                        radius: position_radius as f64,
+                        from_angle,
+                        range,
+                        clockwise,
+                    }
+                } else {
+                    // 行の方向に垂直な、下向きにずらす
+                    let rotation = rotation.unwrap_or(0.0);
+                    let offset = first_line_offset + line_height * i as f64;
+                    let (sin, cos) = rotation.to_radians().sin_cos();
+                    TextPlacement::Straight {
+                        point: (
+                            center_angle_point.0 - sin * offset,
+                            center_angle_point.1 + cos * offset,
+                        ),
+                        rotation,
+                    }
+                };
+                // 文字を表示する順に並べる
+                let line = reorder_visually(line, is_rtl);
+                create_outline_data(
+                    &line,
+                    &options.typography,
+                    font,
+                    placement,
+                    dominant_baseline,
+                )
+            })
+            .collect::<Result<_, _>>()?;
+        if let Some(ruby_typography) = &ruby_typography {
+            for (ruby, point) in ruby_points {
+                let placement = match rotation {
+                    Some(rotation) => TextPlacement::Straight {
+                        point: rotate_point(point, center_angle_point, rotation),
+                        rotation,
+                    },
+                    None if options.orientation == LabelOrientation::Vertical => {
+                        TextPlacement::Vertical { point }
+                    }
+                    None => TextPlacement::Straight {
+                        point,
+                        rotation: 0.0,
+                    },
+                };
+                data.push_str(&create_outline_data(
+                    &ruby,
+                    ruby_typography,
+                    font,
+                    placement,
+                    "central",
+                )?);
+            }
+        }
+        let path_base = Path::new().set("d", data);
+        let group = Group::new().add(Title::new(label));
 
-    Group::new().add(text_under).add(text_body)
+        return Ok(add_with_halo(group, path_base, base_color, options.halo));
+    }
+
+    let mut group = Group::new();
+    if is_altered {
+        group = group.add(Title::new(label));
+    }
+
+    let text_base = if options.orientation == LabelOrientation::Curved {
+        let arc_data = create_data_for_arc(
+            circle_center,
+            position_radius,
+            center_angle,
+            target_angle_range,
+        );
+        let arc_path = Path::new()
+            .set("id", label_path_id)
+            .set("fill", "none")
+            .set("d", arc_data);
+        group = group.add(Definitions::new().add(arc_path));
+
+        let text_path = TextPath::new(lines.concat())
+            .set("href", format!("#{label_path_id}"))
+            .set("startOffset", "50%");
+        TextElement::new("")
+            .set("text-anchor", "middle")
+            .set("dominant-baseline", "central")
+            .add(text_path)
+    } else if options.orientation == LabelOrientation::Vertical {
+        // 右の列から左へ並べ、各列を扇形の中心線上で中央揃えにする
+        lines
+            .iter()
+            .enumerate()
+            .flat_map(|(i, line)| {
+                let offset = first_line_offset + line_height * i as f64;
+                create_vertical_column(
+                    line,
+                    &options.typography,
+                    (center_angle_point.0 - offset, center_angle_point.1),
+                )
+            })
+            .fold(TextElement::new(""), |text_base, tspan| {
+                text_base.add(tspan)
+            })
+            .set("writing-mode", "vertical-rl")
+            // 輪郭にする場合と同じく、文字は全て正立させる
+            .set("style", "text-orientation: upright")
+            .set("text-anchor", "middle")
+    } else {
+        let text_base = if let [line] = lines.as_slice() {
+            TextElement::new(line.as_str()).set("y", center_angle_point.1 + first_line_offset)
+        } else {
+            lines
+                .iter()
+                .enumerate()
+                .fold(TextElement::new(""), |text_base, (i, line)| {
+                    let dy = if i == 0 {
+                        first_line_offset
+                    } else {
+                        line_height
+                    };
+                    text_base.add(
+                        TSpan::new(line.as_str())
+                            .set("x", center_angle_point.0)
+                            .set("dy", dy),
+                    )
+                })
+                .set("y", center_angle_point.1)
+        };
+        let text_base = text_base
+            .set("x", center_angle_point.0)
+            .set("text-anchor", "middle");
+
+        match rotation {
+            Some(rotation) => text_base
+                .set(
+                    "transform",
+                    format!(
+                        "rotate({rotation}, {}, {})",
+                        center_angle_point.0, center_angle_point.1
+                    ),
+                )
+                .set("dominant-baseline", "central"),
+            None => text_base,
+        }
+    };
+
+    let text_base = apply_typography(text_base, &options.typography);
+    let text_base = apply_direction(text_base, is_rtl, is_chart_rtl);
+    let mut group = add_with_halo(group, text_base, base_color, options.halo);
+
+    if let Some(ruby_typography) = &ruby_typography {
+        for (ruby, point) in ruby_points {
+            let ruby_base = TextElement::new(ruby)
+                .set("x", point.0)
+                .set("y", point.1)
+                .set("text-anchor", "middle");
+            let ruby_base = if options.orientation == LabelOrientation::Vertical {
+                ruby_base.set("writing-mode", "vertical-rl")
+            } else if let Some(rotation) = rotation {
+                ruby_base.set(
+                    "transform",
+                    format!(
+                        "rotate({rotation}, {}, {})",
+                        center_angle_point.0, center_angle_point.1
+                    ),
+                )
+            } else {
+                ruby_base
+            };
+            let ruby_base =
+                apply_typography(ruby_base, ruby_typography).set("dominant-baseline", "central");
+            let ruby_base = apply_direction(ruby_base, is_rtl, is_chart_rtl);
+            group = add_with_halo(group, ruby_base, base_color, options.halo);
+        }
+    }
+
+    Ok(group)
+}
+
+/// `point`を、`center`を中心に時計回りに`rotation`度回転する。
+#[cfg(feature = "font")]
+fn rotate_point(point: (f64, f64), center: (f64, f64), rotation: f64) -> (f64, f64) {
+    let (sin, cos) = rotation.to_radians().sin_cos();
+    let (x, y) = (point.0 - center.0, point.1 - center.1);
+    (center.0 + x * cos - y * sin, center.1 + x * sin + y * cos)
+}
+
+/// ラベルを、設定に応じて縁取りを付けて追加する。
+fn add_with_halo<T>(group: Group, base: T, color: Rgb, halo: Option<LabelHalo>) -> Group
+where
+    T: svg::Node + Clone,
+{
+    let mut body = Clone::clone(&base);
+    body.assign("fill", format_rgb(color));
+
+    let Some(halo) = halo else {
+        return group.add(body);
+    };
+
+    let halo_color = halo.color.unwrap_or_else(|| contrasting_color(color));
+    let with_halo = |mut element: T| {
+        element.assign("stroke", format_rgb(halo_color));
+        element.assign("stroke-width", halo.width);
+        if halo.opacity < 1.0 {
+            element.assign("stroke-opacity", halo.opacity);
+        }
+        element
+    };
+
+    if halo.paint_order {
+        // 1つの要素で、縁取りを先に描画する。
+        let mut body = with_halo(body);
+        body.assign("paint-order", "stroke");
+        body.assign("stroke-linejoin", "round");
+        group.add(body)
+    } else {
+        // 縁取りの複製は、読み上げない。
+        let mut halo_copy = with_halo(base);
+        halo_copy.assign("aria-hidden", "true");
+        group.add(halo_copy).add(body)
+    }
+}
+
+/// ラベルを行に分ける。
+///
+/// 弧に沿うラベルは`arc_length`に収まるよう省略する。
+/// 省略記号すら収まらない場合は`None`を返す。
+fn split_lines(
+    label: &str,
+    options: &LabelOptions,
+    arc_length: Option<f64>,
+) -> Option<Vec<String>> {
+    let max_width = options.max_width.map(|max_width| max_width as f64);
+    if options.orientation == LabelOrientation::Curved {
+        let max_width = match (max_width, arc_length) {
+            (Some(max_width), Some(arc_length)) => Some(max_width.min(arc_length)),
+            (max_width, arc_length) => max_width.or(arc_length),
+        };
+        return match max_width {
+            Some(max_width) => {
+                ellipsize(label, &options.typography, max_width, false).map(|text| vec![text])
+            }
+            None => Some(vec![label.to_string()]),
+        };
+    }
+
+    // 縦書きでは、列の高さを`max_width`に収める
+    let is_vertical = options.orientation == LabelOrientation::Vertical;
+    match (max_width, options.overflow) {
+        (None, _) => Some(vec![label.to_string()]),
+        (Some(max_width), LabelOverflow::Ellipsis) => {
+            ellipsize(label, &options.typography, max_width, is_vertical).map(|text| vec![text])
+        }
+        (Some(max_width), LabelOverflow::Wrap { max_lines }) => Some(wrap_text(
+            label,
+            &options.typography,
+            max_width,
+            max_lines.map(|max_lines| max_lines as usize),
+            is_vertical,
+        )),
+    }
+}
+
+/// ラベルの中心から、ラベルを囲む四角形の端までの幅と高さを見積もる。
+///
+/// 水平でも縦書きでもないラベルは、向きに関わらず収まるよう外接円で見積もる。
+pub(crate) fn measure_label_extent(label: &str, options: &LabelOptions) -> (f64, f64) {
+    let (label, rubies) = match options.ruby {
+        Some(_) => parse_ruby(label),
+        None => (label.to_string(), Vec::new()),
+    };
+    let lines = split_lines(&label, options, None).unwrap_or_default();
+
+    let typography = &options.typography;
+    let ruby_size = match options.ruby {
+        Some(ruby) if !rubies.is_empty() => typography.size as f64 * ruby.size_ratio,
+        _ => 0.0,
+    };
+    let width = lines
+        .iter()
+        .map(|line| {
+            measure_text_length(
+                line,
+                typography,
+                options.orientation == LabelOrientation::Vertical,
+            )
+        })
+        .fold(0.0, f64::max);
+    let height = (typography.size as f64 * typography.line_height + ruby_size) * lines.len() as f64;
+
+    match options.orientation {
+        LabelOrientation::Horizontal => (width * 0.5, height * 0.5),
+        LabelOrientation::Vertical => (height * 0.5, width * 0.5),
+        _ => {
+            let radius = (width * 0.5).hypot(height * 0.5);
+            (radius, radius)
+        }
+    }
+}
+
+/// 扇形の弧に沿うパスのデータを作成する。
+///
+/// 文字が左から右へ読めるよう、円の下半分では向きを逆にする。
+fn create_data_for_arc(
+    (circle_center_x, circle_center_y): Coord,
+    position_radius: u32,
+    center_angle: f64,
+    target_angle_range: f64,
+) -> Data {
+    let (from_angle, to_angle, range, clockwise) =
+        calc_arc_angles(center_angle, target_angle_range);
+    let sweep_flag = if clockwise { 1 } else { 0 };
+    let large_arc_flag = if range > PI { 1 } else { 0 };
+
+    let from_point = calc_point(
+        calc_angle_coord(from_angle),
+        circle_center_x,
+        circle_center_y,
+        position_radius,
+    );
+    let to_point = calc_point(
+        calc_angle_coord(to_angle),
+        circle_center_x,
+        circle_center_y,
+        position_radius,
+    );
+
+    Data::new().move_to(from_point).elliptical_arc_to((
+        position_radius,
+        position_radius,
+        0,
+        large_arc_flag,
+        sweep_flag,
+        to_point.0,
+        to_point.1,
+    ))
+}
+
+/// ラベル用の弧の、始点と終点の角度、範囲と、時計回りかどうかを計算する。
+///
+/// 文字が左から右へ読めるよう、円の上半分では時計回り、下半分では反時計回りにする。
+fn calc_arc_angles(center_angle: f64, target_angle_range: f64) -> (f64, f64, f64, bool) {
+    // 円形の場合は始点と終点が重なってしまうため、わずかに狭める。
+    let range = target_angle_range.abs().min(TAU * 0.999);
+    let half_range = range * 0.5;
+
+    if center_angle <= PI {
+        (
+            center_angle + half_range,
+            center_angle - half_range,
+            range,
+            true,
+        )
+    } else {
+        (
+            center_angle - half_range,
+            center_angle + half_range,
+            range,
+            false,
+        )
+    }
+}
+
+/// 回転の角度 (度数法、時計回り) を計算する。水平・弧に沿う場合は`None`。
+fn calc_rotation(orientation: LabelOrientation, center_angle: f64) -> Option<f64> {
+    // SVGの座標系はy軸が下向きなので、回転の向きが逆になる。
+    let radial_degrees = -center_angle.to_degrees();
+    let degrees = match orientation {
+        LabelOrientation::Horizontal | LabelOrientation::Curved | LabelOrientation::Vertical => {
+            return None
+        }
+        LabelOrientation::Radial => radial_degrees,
+        LabelOrientation::Tangential => radial_degrees + 90.0,
+    };
+
+    Some(upright_degrees(degrees))
+}
+
+/// `(x, y)`を中心とする、縦書きの1列を作成する。
+///
+/// 縦中横にする数字とそれ以外の塊ごとに、位置を指定した`TSpan`にする。
+/// 位置を指定するため、要素の間の改行は描画に影響しない。
+fn create_vertical_column(line: &str, typography: &Typography, (x, y): (f64, f64)) -> Vec<TSpan> {
+    let mut top = y - measure_text_length(line, typography, true) * 0.5;
+
+    split_upright_digits(line)
+        .into_iter()
+        .map(|(run, is_upright)| {
+            let length = measure_text_length(&run, typography, true);
+            let tspan = TSpan::new(run).set("x", x).set("y", top + length * 0.5);
+            top += length;
+            if is_upright {
+                tspan.set("style", "text-combine-upright: all")
+            } else {
+                tspan
+            }
+        })
+        .collect()
+}
+
+/// 文字が上下逆さまにならないよう、角度を[-90, 90)の範囲に収める。
+fn upright_degrees(degrees: f64) -> f64 {
+    let degrees = degrees.rem_euclid(360.0);
+    if degrees >= 270.0 {
+        degrees - 360.0
+    } else if degrees >= 90.0 {
+        degrees - 180.0
+    } else {
+        degrees
+    }
 }
 
 #[cfg(test)]
@@ -63,24 +578,39 @@ mod tests {
     use super::*;
 
     mod create_label {
-        use std::{f64::consts::FRAC_PI_2, path::PathBuf};
+        use std::{
+            f64::consts::{FRAC_PI_2, TAU},
+            path::PathBuf,
+        };
 
         use svg::Document;
 
         use super::*;
+        use crate::options::{LabelThreshold, Typography};
 
         #[test]
         fn when_top_of_circle_brack() {
+            let options = LabelOptions {
+                color: LabelColor::Fixed((0, 0, 0)),
+                typography: Typography {
+                    font_family: "ＭＳ 明朝".to_string(),
+                    size: 10,
+                    ..Default::default()
+                },
+                position_radius: Some(40),
+                ..Default::default()
+            };
             let label = crate_label(
                 (50, 50),
-                (0, 0, 0),
-                "ＭＳ 明朝",
-                10,
-                40,
+                &options,
                 FRAC_PI_2,
                 FRAC_PI_2,
                 "BlackLabel",
-            );
+                "#999",
+                "l_0",
+                false,
+            )
+            .unwrap();
             let document = Document::new().set("viewBox", "0, 0, 100, 100").add(label);
             let save_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
                 .join("test_target/test_create_label_top-of-circle-black.svg");
@@ -89,20 +619,869 @@ mod tests {
 
         #[test]
         fn when_bottom_of_circle_white() {
+            let options = LabelOptions {
+                color: LabelColor::Fixed((255, 255, 255)),
+                typography: Typography {
+                    font_family: "ＭＳ ゴシック".to_string(),
+                    size: 10,
+                    ..Default::default()
+                },
+                position_radius: Some(40),
+                ..Default::default()
+            };
             let label = crate_label(
                 (50, 50),
-                (255, 255, 255),
-                "ＭＳ ゴシック",
-                10,
-                40,
+                &options,
                 -FRAC_PI_2,
                 FRAC_PI_2,
                 "WhiteLabel",
-            );
+                "#999",
+                "l_0",
+                false,
+            )
+            .unwrap();
             let document = Document::new().set("viewBox", "0, 0, 100, 100").add(label);
             let save_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
                 .join("test_target/test_create_label_bottom-of-circle-white.svg");
             svg::save(save_path, &document).unwrap();
         }
+
+        #[test]
+        fn empty_when_smaller_than_threshold() {
+            let options = LabelOptions {
+                hide_below: Some(LabelThreshold::Ratio(0.01)),
+                ..Default::default()
+            };
+            let label = crate_label(
+                (50, 50),
+                &options,
+                FRAC_PI_2,
+                TAU * 0.005,
+                "Tiny",
+                "#999",
+                "l_0",
+                false,
+            )
+            .unwrap();
+            assert!(label.get_children().is_empty());
+
+            let label = crate_label(
+                (50, 50),
+                &options,
+                FRAC_PI_2,
+                TAU * 0.02,
+                "Small",
+                "#999",
+                "l_0",
+                false,
+            )
+            .unwrap();
+            assert!(!label.get_children().is_empty());
+        }
+
+        #[test]
+        fn success_when_rotated() {
+            let mut document = Document::new().set("viewBox", "0, 0, 100, 100");
+            for orientation in [LabelOrientation::Radial, LabelOrientation::Tangential] {
+                let options = LabelOptions {
+                    position_radius: Some(35),
+                    orientation,
+                    ..Default::default()
+                };
+                for i in 0..8 {
+                    let center_angle = TAU * i as f64 / 8.0;
+                    let label = crate_label(
+                        (50, 50),
+                        &options,
+                        center_angle,
+                        0.5,
+                        "Label",
+                        "#999",
+                        "l_0",
+                        false,
+                    )
+                    .unwrap();
+                    document = document.add(label);
+                }
+            }
+            let save_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("test_target/test_create_label_rotated.svg");
+            svg::save(save_path, &document).unwrap();
+        }
+    }
+
+    mod create_label_auto_color {
+        use std::path::PathBuf;
+
+        use svg::Document;
+
+        use super::*;
+
+        #[test]
+        fn success_when_dark_and_light_pies() {
+            let options = LabelOptions {
+                color: LabelColor::Auto,
+                ..Default::default()
+            };
+
+            let dark = crate_label(
+                (50, 50),
+                &options,
+                PI * 0.5,
+                1.0,
+                "Dark",
+                "navy",
+                "l_0",
+                false,
+            )
+            .unwrap();
+            let dark_text = dark.to_string();
+            assert!(dark_text.contains(r#"fill="rgb(255, 255, 255)""#));
+            assert!(dark_text.contains(r#"stroke="rgb(0, 0, 0)""#));
+
+            let light = crate_label(
+                (50, 50),
+                &options,
+                PI * 1.5,
+                1.0,
+                "Light",
+                "#ffe",
+                "l_1",
+                false,
+            )
+            .unwrap();
+            let light_text = light.to_string();
+            assert!(light_text.contains(r#"fill="rgb(0, 0, 0)""#));
+            assert!(light_text.contains(r#"stroke="rgb(255, 255, 255)""#));
+
+            let document = Document::new()
+                .set("viewBox", "0, 0, 100, 100")
+                .add(dark)
+                .add(light);
+            let save_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("test_target/test_create_label_auto-color.svg");
+            svg::save(save_path, &document).unwrap();
+        }
+    }
+
+    mod create_label_halo {
+        use std::path::PathBuf;
+
+        use svg::Document;
+
+        use super::*;
+        use crate::options::LabelHalo;
+
+        #[test]
+        fn success_when_variations_of_halo() {
+            let no_halo = LabelOptions {
+                halo: None,
+                ..Default::default()
+            };
+            let label =
+                crate_label((50, 50), &no_halo, 0.0, 1.0, "None", "#999", "l_0", false).unwrap();
+            assert_eq!(label.get_children().len(), 1);
+            assert!(!label.to_string().contains("stroke"));
+
+            let paint_order = LabelOptions {
+                halo: Some(LabelHalo {
+                    color: Some((255, 255, 0)),
+                    width: 3.0,
+                    opacity: 0.5,
+                    paint_order: true,
+                }),
+                ..Default::default()
+            };
+            let label = crate_label(
+                (50, 50),
+                &paint_order,
+                PI * 0.5,
+                1.0,
+                "PaintOrder",
+                "#999",
+                "l_1",
+                false,
+            )
+            .unwrap();
+            assert_eq!(label.get_children().len(), 1);
+            let text = label.to_string();
+            assert!(text.contains(r#"paint-order="stroke""#));
+            assert!(text.contains(r#"stroke="rgb(255, 255, 0)""#));
+            assert!(text.contains(r#"stroke-opacity="0.5""#));
+            assert!(text.contains(r#"stroke-width="3""#));
+
+            let duplicated = LabelOptions::default();
+            let duplicated_label = crate_label(
+                (50, 50),
+                &duplicated,
+                PI * 1.5,
+                1.0,
+                "Duplicated",
+                "#999",
+                "l_2",
+                false,
+            )
+            .unwrap();
+            assert_eq!(duplicated_label.get_children().len(), 2);
+            // 縁取りの複製だけを、読み上げから外す
+            let text = duplicated_label.to_string();
+            assert_eq!(text.matches(r#"aria-hidden="true""#).count(), 1);
+            assert!(text.contains(r#"<text aria-hidden="true" font-family"#));
+
+            let document = Document::new()
+                .set("viewBox", "0, 0, 100, 100")
+                .add(label)
+                .add(duplicated_label);
+            let save_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("test_target/test_create_label_halo.svg");
+            svg::save(save_path, &document).unwrap();
+        }
+    }
+
+    mod create_label_vertical {
+        use std::path::PathBuf;
+
+        use svg::Document;
+
+        use super::*;
+
+        #[test]
+        fn success_when_vertical() {
+            let mut document = Document::new().set("viewBox", "0, 0, 100, 100");
+            let options = LabelOptions {
+                orientation: LabelOrientation::Vertical,
+                ..Default::default()
+            };
+            let label = crate_label(
+                (50, 50),
+                &options,
+                PI * 0.5,
+                1.0,
+                "第12回",
+                "#999",
+                "l_0",
+                false,
+            )
+            .unwrap();
+            let text = label.to_string();
+            assert!(text.contains(r#"style="text-orientation: upright""#));
+            assert!(text.contains(r#"writing-mode="vertical-rl""#));
+            // 縦中横の数字も1文字分の高さで送り、塊ごとに位置を指定する
+            assert!(text.contains(r#"<tspan x="50" y="20">第</tspan>"#));
+            assert!(text
+                .contains(r#"<tspan style="text-combine-upright: all" x="50" y="30">12</tspan>"#));
+            assert!(text.contains(r#"<tspan x="50" y="40">回</tspan>"#));
+            assert!(!text.contains("transform"));
+            document = document.add(label);
+
+            let options = LabelOptions {
+                orientation: LabelOrientation::Vertical,
+                max_width: Some(30),
+                overflow: LabelOverflow::Wrap { max_lines: None },
+                ..Default::default()
+            };
+            let label = crate_label(
+                (50, 100),
+                &options,
+                PI * 0.5,
+                1.0,
+                "東京都大阪府",
+                "#999",
+                "l_1",
+                false,
+            )
+            .unwrap();
+            let text = label.to_string();
+            // 1列目は右に置く
+            assert!(text.contains(r#"<tspan x="56" y="80">東京都</tspan>"#));
+            assert!(text.contains(r#"<tspan x="44" y="80">大阪府</tspan>"#));
+            document = document.add(label);
+
+            // 正立させた文字は全角の高さで送るため、その高さで折り返す
+            let label = crate_label(
+                (80, 100),
+                &options,
+                PI * 0.5,
+                1.0,
+                "abcdef",
+                "#999",
+                "l_2",
+                false,
+            )
+            .unwrap();
+            let text = label.to_string();
+            assert!(text.contains(r#"<tspan x="86" y="80">abc</tspan>"#));
+            assert!(text.contains(r#"<tspan x="74" y="80">def</tspan>"#));
+            document = document.add(label);
+
+            let save_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("test_target/test_create_label_vertical.svg");
+            svg::save(save_path, &document).unwrap();
+        }
+    }
+
+    mod create_label_ruby {
+        use std::path::PathBuf;
+
+        use svg::Document;
+
+        use super::*;
+        use crate::options::RubyOptions;
+
+        #[test]
+        fn success_when_ruby() {
+            let mut document = Document::new().set("viewBox", "0, 0, 200, 200");
+            let options = LabelOptions {
+                ruby: Some(RubyOptions::default()),
+                halo: None,
+                ..Default::default()
+            };
+            let label = crate_label(
+                (100, 100),
+                &options,
+                PI * 0.5,
+                1.0,
+                "東京《とうきょう》",
+                "#999",
+                "l_0",
+                false,
+            )
+            .unwrap();
+            let text = label.to_string();
+            // 親文字は、ルビの分だけ下にずれる
+            assert!(text.contains(r#"text-anchor="middle" x="100" y="82.5">"#));
+            assert!(text.contains(
+                r#"dominant-baseline="central" fill="rgb(0, 0, 0)" font-family="sans-serif" font-size="5" text-anchor="middle" x="100" y="72">"#
+            ));
+            assert!(text.contains("とうきょう"));
+            assert!(!text.contains("《"));
+            document = document.add(label);
+
+            let options = LabelOptions {
+                ruby: Some(RubyOptions::default()),
+                orientation: LabelOrientation::Vertical,
+                ..Default::default()
+            };
+            let label = crate_label(
+                (100, 100),
+                &options,
+                PI * 1.5,
+                1.0,
+                "｜大阪《おおさか》府",
+                "#999",
+                "l_1",
+                false,
+            )
+            .unwrap();
+            let text = label.to_string();
+            assert!(text.contains("おおさか"));
+            assert!(text.contains(r#"writing-mode="vertical-rl""#));
+            document = document.add(label);
+
+            let options = LabelOptions {
+                ruby: Some(RubyOptions::default()),
+                orientation: LabelOrientation::Radial,
+                max_width: Some(30),
+                overflow: LabelOverflow::Wrap { max_lines: None },
+                ..Default::default()
+            };
+            let label = crate_label(
+                (100, 100),
+                &options,
+                0.0,
+                1.0,
+                "東京《とうきょう》と京都《きょうと》",
+                "#999",
+                "l_2",
+                false,
+            )
+            .unwrap();
+            let text = label.to_string();
+            assert!(text.contains("きょうと"));
+            // 行の間隔にルビの大きさが加わる
+            assert!(text.contains(r#"<tspan dy="17" x="120">京都</tspan>"#));
+            assert!(text.contains(r#"x="115" y="86.5">"#));
+            assert!(text.contains(r#"x="120" y="103.5">"#));
+            document = document.add(label);
+
+            let save_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("test_target/test_create_label_ruby.svg");
+            svg::save(save_path, &document).unwrap();
+        }
+
+        #[test]
+        fn success_when_ruby_disabled() {
+            let label = crate_label(
+                (100, 100),
+                &LabelOptions::default(),
+                PI * 0.5,
+                1.0,
+                "東京《とうきょう》",
+                "#999",
+                "l_0",
+                false,
+            )
+            .unwrap();
+            assert!(label.to_string().contains("東京《とうきょう》"));
+        }
+    }
+
+    mod create_label_direction {
+        use super::*;
+        use crate::options::TextDirection;
+
+        #[test]
+        fn success_when_rtl() {
+            let options = LabelOptions::default();
+            let label = crate_label(
+                (50, 50),
+                &options,
+                PI * 0.5,
+                1.0,
+                "אדום (50%)",
+                "#999",
+                "l_0",
+                false,
+            )
+            .unwrap();
+            assert!(label
+                .to_string()
+                .contains(r#"direction="rtl" font-family="sans-serif" font-size="10" stroke="rgb(255, 255, 255)" stroke-width="2" text-anchor="middle" unicode-bidi="embed""#));
+
+            // 左から右へ書くラベルは、グラフが右から左へ書く場合のみ向きを設定する
+            let label = crate_label(
+                (50, 50),
+                &options,
+                PI * 0.5,
+                1.0,
+                "Red",
+                "#999",
+                "l_1",
+                false,
+            )
+            .unwrap();
+            assert!(!label.to_string().contains("direction"));
+            let label = crate_label(
+                (50, 50),
+                &options,
+                PI * 0.5,
+                1.0,
+                "Red",
+                "#999",
+                "l_1",
+                true,
+            )
+            .unwrap();
+            assert!(label.to_string().contains(r#"direction="ltr""#));
+
+            let options = LabelOptions {
+                direction: TextDirection::Rtl,
+                ..Default::default()
+            };
+            let label = crate_label(
+                (50, 50),
+                &options,
+                PI * 0.5,
+                1.0,
+                "Red",
+                "#999",
+                "l_2",
+                false,
+            )
+            .unwrap();
+            assert!(label.to_string().contains(r#"direction="rtl""#));
+        }
+    }
+
+    mod create_label_max_width {
+        use std::path::PathBuf;
+
+        use svg::Document;
+
+        use super::*;
+
+        #[test]
+        fn success_when_ellipsized() {
+            let options = LabelOptions {
+                max_width: Some(60),
+                ..Default::default()
+            };
+            let label = crate_label(
+                (100, 100),
+                &options,
+                PI * 0.5,
+                1.0,
+                "Other operating expenses",
+                "#999",
+                "l_0",
+                false,
+            )
+            .unwrap();
+            let text = label.to_string();
+            assert!(text.contains("<title>Other operating expenses</title>"));
+            assert!(text.contains("Other ope…"));
+
+            let label = crate_label(
+                (100, 100),
+                &options,
+                PI * 0.5,
+                1.0,
+                "Other",
+                "#999",
+                "l_0",
+                false,
+            )
+            .unwrap();
+            assert!(!label.to_string().contains("<title>"));
+        }
+
+        #[test]
+        fn success_when_wrapped() {
+            let mut document = Document::new().set("viewBox", "0, 0, 200, 200");
+            let options = LabelOptions {
+                max_width: Some(60),
+                overflow: LabelOverflow::Wrap { max_lines: None },
+                halo: None,
+                ..Default::default()
+            };
+            let label = crate_label(
+                (100, 100),
+                &options,
+                PI * 0.5,
+                1.0,
+                "Other operating expenses (non-recurring)",
+                "#999",
+                "l_0",
+                false,
+            )
+            .unwrap();
+            let text = label.to_string();
+            assert!(text.contains("<title>Other operating expenses (non-recurring)</title>"));
+            assert_eq!(text.matches("<tspan").count(), 5);
+            assert!(text.contains(r#"<tspan dy="-24" x="100">Other</tspan>"#));
+            assert!(text.contains(r#"<tspan dy="12" x="100">(non-</tspan>"#));
+            document = document.add(label);
+
+            let options = LabelOptions {
+                max_width: Some(40),
+                overflow: LabelOverflow::Wrap { max_lines: Some(2) },
+                orientation: LabelOrientation::Radial,
+                ..Default::default()
+            };
+            let label = crate_label(
+                (100, 100),
+                &options,
+                PI * 1.75,
+                1.0,
+                "東京都、大阪府、京都府",
+                "#999",
+                "l_1",
+                false,
+            )
+            .unwrap();
+            let text = label.to_string();
+            assert!(text.contains(">東京都、<"));
+            assert!(text.contains(">大阪府…<"));
+            document = document.add(label);
+
+            let save_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("test_target/test_create_label_wrapped.svg");
+            svg::save(save_path, &document).unwrap();
+        }
+    }
+
+    #[cfg(feature = "font")]
+    mod create_label_outline {
+        use std::path::PathBuf;
+
+        use svg::Document;
+
+        use super::*;
+        use crate::font::{tests::create_test_font, Font};
+        use crate::options::Typography;
+
+        #[test]
+        fn success_when_font_specified() {
+            let mut document = Document::new().set("viewBox", "0, 0, 100, 100");
+            for (i, orientation) in [
+                LabelOrientation::Horizontal,
+                LabelOrientation::Radial,
+                LabelOrientation::Curved,
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                let options = LabelOptions {
+                    typography: Typography {
+                        font: Some(Font::from_bytes(create_test_font()).unwrap()),
+                        ..Default::default()
+                    },
+                    position_radius: Some(30),
+                    orientation,
+                    outline: true,
+                    ..Default::default()
+                };
+                let label = crate_label(
+                    (50, 50),
+                    &options,
+                    PI * 0.5 + i as f64 * 2.0,
+                    1.0,
+                    "AB東",
+                    "#999",
+                    "l_0",
+                    false,
+                )
+                .unwrap();
+                let text = label.to_string();
+                assert!(text.contains("<title>"));
+                assert!(text.contains("AB東"));
+                assert!(text.contains("<path"));
+                assert!(!text.contains("<text"));
+                document = document.add(label);
+            }
+            let save_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("test_target/test_create_label_outline.svg");
+            svg::save(save_path, &document).unwrap();
+        }
+
+        #[test]
+        fn success_when_ruby() {
+            let options = LabelOptions {
+                typography: Typography {
+                    font: Some(Font::from_bytes(create_test_font()).unwrap()),
+                    ..Default::default()
+                },
+                ruby: Some(crate::options::RubyOptions::default()),
+                halo: None,
+                outline: true,
+                ..Default::default()
+            };
+            let label = crate_label(
+                (50, 50),
+                &options,
+                PI * 0.5,
+                1.0,
+                "｜東《A》",
+                "#999",
+                "l_0",
+                false,
+            )
+            .unwrap();
+            let text = label.to_string();
+            assert!(text.contains("<title>東</title>"));
+            // 親文字とルビの輪郭
+            assert_eq!(text.matches('M').count(), 2);
+        }
+
+        #[test]
+        fn success_when_rtl() {
+            let options = LabelOptions {
+                typography: Typography {
+                    font: Some(Font::from_bytes(create_test_font()).unwrap()),
+                    ..Default::default()
+                },
+                direction: crate::options::TextDirection::Rtl,
+                outline: true,
+                ..Default::default()
+            };
+            let rtl = crate_label(
+                (50, 50),
+                &options,
+                PI * 0.5,
+                1.0,
+                "AB",
+                "#999",
+                "l_0",
+                false,
+            )
+            .unwrap();
+            let options = LabelOptions {
+                direction: crate::options::TextDirection::Ltr,
+                ..options
+            };
+            let ltr = crate_label(
+                (50, 50),
+                &options,
+                PI * 0.5,
+                1.0,
+                "AB",
+                "#999",
+                "l_0",
+                false,
+            )
+            .unwrap();
+            // 右から左へ書くラベルの中でも、左から右へ書く語は順番を保つ
+            let path = |label: Group| {
+                label
+                    .get_children()
+                    .iter()
+                    .map(|child| child.to_string())
+                    .filter(|child| child.starts_with("<path"))
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(path(rtl), path(ltr));
+        }
+
+        #[test]
+        fn failed_when_font_not_specified() {
+            let options = LabelOptions {
+                outline: true,
+                ..Default::default()
+            };
+            let result = crate_label((50, 50), &options, PI * 0.5, 1.0, "A", "#999", "l_0", false);
+            assert_eq!(result.err(), Some(PieChartError::FontRequired));
+        }
+    }
+
+    mod create_label_curved {
+        use std::path::PathBuf;
+
+        use svg::Document;
+
+        use super::*;
+
+        #[test]
+        fn success_when_top_and_bottom_of_circle() {
+            let options = LabelOptions {
+                position_radius: Some(35),
+                orientation: LabelOrientation::Curved,
+                ..Default::default()
+            };
+            let top = crate_label(
+                (50, 50),
+                &options,
+                PI * 0.5,
+                PI * 0.5,
+                "Top of circle",
+                "#999",
+                "l_0",
+                false,
+            )
+            .unwrap();
+            let bottom = crate_label(
+                (50, 50),
+                &options,
+                PI * 1.5,
+                PI * 0.5,
+                "Bottom of circle",
+                "#999",
+                "l_1",
+                false,
+            )
+            .unwrap();
+            let document = Document::new()
+                .set("viewBox", "0, 0, 100, 100")
+                .add(top)
+                .add(bottom);
+            let save_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("test_target/test_create_label_curved.svg");
+            svg::save(save_path, &document).unwrap();
+        }
+
+        #[test]
+        fn ellipsized_when_longer_than_arc() {
+            let options = LabelOptions {
+                position_radius: Some(20),
+                orientation: LabelOrientation::Curved,
+                ..Default::default()
+            };
+            let label = crate_label(
+                (50, 50),
+                &options,
+                PI * 0.5,
+                1.0,
+                "LongLongLabel",
+                "#999",
+                "l_0",
+                false,
+            )
+            .unwrap();
+            assert!(label.to_string().contains("Lo…"));
+
+            let label = crate_label(
+                (50, 50),
+                &options,
+                PI * 0.5,
+                0.1,
+                "LongLongLabel",
+                "#999",
+                "l_0",
+                false,
+            )
+            .unwrap();
+            assert!(label.get_children().is_empty());
+        }
+    }
+
+    mod create_data_for_arc {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            let data = create_data_for_arc((50, 50), 10, PI * 0.5, PI);
+            let path = Path::new().set("d", data).to_string();
+            assert!(path.contains("M40,50 A10,10,0,0,1,60,50"), "{path}");
+
+            let data = create_data_for_arc((50, 50), 10, PI * 1.5, PI);
+            let path = Path::new().set("d", data).to_string();
+            assert!(path.contains("M40,50 A10,10,0,0,0,60,50"), "{path}");
+        }
+    }
+
+    mod calc_rotation {
+        use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            assert_eq!(calc_rotation(LabelOrientation::Horizontal, 0.0), None);
+            assert_eq!(calc_rotation(LabelOrientation::Vertical, FRAC_PI_4), None);
+
+            assert_eq!(calc_rotation(LabelOrientation::Radial, 0.0), Some(0.0));
+            assert_eq!(
+                calc_rotation(LabelOrientation::Radial, FRAC_PI_2),
+                Some(-90.0)
+            );
+            assert_eq!(calc_rotation(LabelOrientation::Radial, PI), Some(0.0));
+            assert_eq!(
+                calc_rotation(LabelOrientation::Radial, FRAC_PI_4),
+                Some(-45.0)
+            );
+            assert_eq!(
+                calc_rotation(LabelOrientation::Radial, PI - FRAC_PI_4),
+                Some(45.0)
+            );
+
+            assert_eq!(
+                calc_rotation(LabelOrientation::Tangential, FRAC_PI_2),
+                Some(0.0)
+            );
+            assert_eq!(
+                calc_rotation(LabelOrientation::Tangential, PI + FRAC_PI_2),
+                Some(0.0)
+            );
+            assert_eq!(
+                calc_rotation(LabelOrientation::Tangential, 0.0),
+                Some(-90.0)
+            );
+            assert_eq!(calc_rotation(LabelOrientation::Tangential, PI), Some(-90.0));
+        }
+    }
+
+    mod upright_degrees {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            assert_eq!(upright_degrees(0.0), 0.0);
+            assert_eq!(upright_degrees(89.0), 89.0);
+            assert_eq!(upright_degrees(90.0), -90.0);
+            assert_eq!(upright_degrees(180.0), 0.0);
+            assert_eq!(upright_degrees(-180.0), 0.0);
+            assert_eq!(upright_degrees(-45.0), -45.0);
+            assert_eq!(upright_degrees(-135.0), 45.0);
+            assert_eq!(upright_degrees(450.0), -90.0);
+        }
     }
 }
@@ -0,0 +1,336 @@
+use crate::options::{Insets, LegendPosition};
+use crate::util::Coord;
+
+/// グラフの周りの余白と、要素の間隔。
+pub(crate) const SPACING: f64 = 4.0;
+/// 重なった円を離す処理の、最大の繰り返し回数。
+const SEPARATION_ITERATIONS: usize = 100;
+/// 重なりとみなさない誤差。
+const SEPARATION_TOLERANCE: f64 = 1e-9;
+
+/// Bounding box of a chart.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    /// X coordinate of the left edge.
+    pub x: f64,
+    /// Y coordinate of the top edge.
+    pub y: f64,
+    /// Width.
+    pub width: f64,
+    /// Height.
+    pub height: f64,
+}
+
+impl BoundingBox {
+    /// 幅と高さから、原点を左上とする範囲を作成する。
+    pub(crate) fn from_size(width: u32, height: u32) -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            width: width as f64,
+            height: height as f64,
+        }
+    }
+}
+
+/// ラベルの中心の方向と、ラベルの中心から端までの幅と高さ。
+pub(crate) type LabelExtent = ((f64, f64), (f64, f64));
+
+/// 四角形の範囲。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Area {
+    pub left: f64,
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+}
+
+impl Area {
+    /// 幅と高さから、全体の範囲を作成する。
+    pub fn from_size(width: u32, height: u32) -> Self {
+        Self {
+            left: 0.0,
+            top: 0.0,
+            right: width as f64,
+            bottom: height as f64,
+        }
+    }
+
+    /// 各辺を、`insets`と`extra`の分だけ内側へ縮める。
+    pub fn shrink(&self, insets: &Insets, extra: f64) -> Self {
+        Self {
+            left: self.left + insets.left as f64 + extra,
+            top: self.top + insets.top as f64 + extra,
+            right: self.right - insets.right as f64 - extra,
+            bottom: self.bottom - insets.bottom as f64 - extra,
+        }
+    }
+
+    pub fn width(&self) -> f64 {
+        self.right - self.left
+    }
+
+    pub fn height(&self) -> f64 {
+        self.bottom - self.top
+    }
+}
+
+/// 上下の文字列を除いた、円と凡例を置く範囲を計算する。
+pub(crate) fn calc_plot_area(content_area: &Area, header_height: f64, footer_height: f64) -> Area {
+    Area {
+        top: content_area.top + with_spacing(header_height),
+        bottom: content_area.bottom - with_spacing(footer_height),
+        ..*content_area
+    }
+}
+
+/// `area`の`position`の側に凡例を置き、凡例の左上の位置を返す。
+///
+/// `area`からは、凡例の分を除く。
+pub(crate) fn place_legend(
+    area: &mut Area,
+    position: LegendPosition,
+    (width, height): (f64, f64),
+) -> (f64, f64) {
+    let center_x = (area.left + area.right - width) * 0.5;
+    let center_y = ((area.top + area.bottom - height) * 0.5).max(area.top + SPACING);
+
+    match position {
+        LegendPosition::Top => {
+            let origin = (center_x, area.top + SPACING);
+            area.top += height + SPACING * 2.0;
+            origin
+        }
+        LegendPosition::Bottom => {
+            let origin = (center_x, area.bottom - SPACING - height);
+            area.bottom -= height + SPACING * 2.0;
+            origin
+        }
+        LegendPosition::Left => {
+            let origin = (area.left + SPACING, center_y);
+            area.left += width + SPACING * 2.0;
+            origin
+        }
+        LegendPosition::Right => {
+            let origin = (area.right - SPACING - width, center_y);
+            area.right -= width + SPACING * 2.0;
+            origin
+        }
+    }
+}
+
+/// `area`の中央に円を置き、円の中心と、円が収まるよう縮める倍率を返す。
+pub(crate) fn fit_circle(area: &Area, circle_radius: u32) -> (Coord, f64) {
+    let center = (
+        ((area.left + area.right) * 0.5).round().max(0.0) as u32,
+        ((area.top + area.bottom) * 0.5).round().max(0.0) as u32,
+    );
+    let diameter = circle_radius as f64 * 2.0;
+    let available = area.width().min(area.height()).max(0.0);
+    let scale = if diameter > available {
+        available / diameter
+    } else {
+        1.0
+    };
+
+    (center, scale)
+}
+
+/// `area`の中央に置いた円とラベルが収まる、最大の半径を計算する。
+///
+/// `position_ratio`は、円の半径に対するラベルの位置の比率。
+/// `None`の場合、ラベルの位置は半径によらないものとする。
+pub(crate) fn fit_radius(area: &Area, labels: &[LabelExtent], position_ratio: Option<f64>) -> u32 {
+    let half_width = area.width() * 0.5;
+    let half_height = area.height() * 0.5;
+
+    let mut radius = half_width.min(half_height);
+    if let Some(ratio) = position_ratio {
+        for ((direction_x, direction_y), (extent_x, extent_y)) in labels {
+            for (half, direction, extent) in [
+                (half_width, direction_x, extent_x),
+                (half_height, direction_y, extent_y),
+            ] {
+                let distance = ratio * direction.abs();
+                if distance > 0.0 {
+                    radius = radius.min((half - extent) / distance);
+                }
+            }
+        }
+    }
+
+    radius.floor().max(0.0) as u32
+}
+
+/// 重なった円を離す (円の位置と半径の組)。
+///
+/// 重なった2つの円を、中心を結ぶ方向へ半分ずつ動かすことを繰り返す。
+/// 円の間は、`gap`以上空ける。
+pub(crate) fn separate_circles(circles: &mut [(f64, f64, f64)], gap: f64) {
+    for _ in 0..SEPARATION_ITERATIONS {
+        let mut is_moved = false;
+        for i in 0..circles.len() {
+            for j in (i + 1)..circles.len() {
+                let (x_i, y_i, radius_i) = circles[i];
+                let (x_j, y_j, radius_j) = circles[j];
+                let (dx, dy) = (x_j - x_i, y_j - y_i);
+                let distance = dx.hypot(dy);
+                let overlap = radius_i + radius_j + gap - distance;
+                if overlap <= SEPARATION_TOLERANCE {
+                    continue;
+                }
+
+                // 同じ位置の場合は、横に並べる
+                let (direction_x, direction_y) = if distance > 0.0 {
+                    (dx / distance, dy / distance)
+                } else {
+                    (1.0, 0.0)
+                };
+                let half = overlap * 0.5;
+                circles[i].0 -= direction_x * half;
+                circles[i].1 -= direction_y * half;
+                circles[j].0 += direction_x * half;
+                circles[j].1 += direction_y * half;
+                is_moved = true;
+            }
+        }
+        if !is_moved {
+            break;
+        }
+    }
+}
+
+/// 文字列がある場合、前後に間隔を加える。
+fn with_spacing(height: f64) -> f64 {
+    if height > 0.0 {
+        height + SPACING * 2.0
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod calc_plot_area {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            let content_area = Area::from_size(100, 100);
+            assert_eq!(
+                calc_plot_area(&content_area, 0.0, 0.0),
+                Area {
+                    left: 0.0,
+                    top: 0.0,
+                    right: 100.0,
+                    bottom: 100.0,
+                }
+            );
+            // 余白10、上に高さ12、下に高さ8の文字列
+            let content_area = Area::from_size(200, 200).shrink(&Insets::uniform(10), 0.0);
+            assert_eq!(
+                calc_plot_area(&content_area, 12.0, 8.0),
+                Area {
+                    left: 10.0,
+                    top: 30.0,
+                    right: 190.0,
+                    bottom: 174.0,
+                }
+            );
+        }
+    }
+
+    mod place_legend {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            let mut area = Area::from_size(200, 100);
+            assert_eq!(
+                place_legend(&mut area, LegendPosition::Right, (50.0, 30.0)),
+                (146.0, 35.0)
+            );
+            assert_eq!(area.right, 142.0);
+
+            let mut area = Area::from_size(200, 100);
+            assert_eq!(
+                place_legend(&mut area, LegendPosition::Bottom, (50.0, 30.0)),
+                (75.0, 66.0)
+            );
+            assert_eq!(area.bottom, 62.0);
+        }
+    }
+
+    mod fit_circle {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            let area = Area::from_size(100, 100);
+            assert_eq!(fit_circle(&area, 40), ((50, 50), 1.0));
+
+            // 収まらない場合は縮める
+            let area = Area {
+                left: 0.0,
+                top: 0.0,
+                right: 142.0,
+                bottom: 100.0,
+            };
+            assert_eq!(fit_circle(&area, 80), ((71, 50), 0.625));
+        }
+    }
+
+    mod fit_radius {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            let area = Area::from_size(200, 100);
+            assert_eq!(fit_radius(&area, &[], Some(0.5)), 50);
+
+            // 右に幅20のラベルを、半径の1.2倍の位置に置く
+            let labels = [((1.0, 0.0), (10.0, 5.0))];
+            assert_eq!(fit_radius(&area, &labels, Some(1.2)), 50);
+            assert_eq!(fit_radius(&area, &labels, Some(2.0)), 45);
+            // 上下のラベル
+            let labels = [((0.0, -1.0), (10.0, 5.0))];
+            assert_eq!(fit_radius(&area, &labels, Some(1.5)), 30);
+            // 位置が半径によらない場合
+            assert_eq!(fit_radius(&area, &labels, None), 50);
+        }
+    }
+
+    mod separate_circles {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            // 重ならない円は動かさない
+            let mut circles = [(0.0, 0.0, 10.0), (30.0, 0.0, 10.0)];
+            separate_circles(&mut circles, 2.0);
+            assert_eq!(circles, [(0.0, 0.0, 10.0), (30.0, 0.0, 10.0)]);
+
+            let mut circles = [(0.0, 0.0, 10.0), (15.0, 0.0, 10.0)];
+            separate_circles(&mut circles, 2.0);
+            assert_eq!(circles, [(-3.5, 0.0, 10.0), (18.5, 0.0, 10.0)]);
+
+            // 同じ位置の円は、横に並べる
+            let mut circles = [(0.0, 0.0, 10.0), (0.0, 0.0, 10.0)];
+            separate_circles(&mut circles, 0.0);
+            assert_eq!(circles, [(-10.0, 0.0, 10.0), (10.0, 0.0, 10.0)]);
+
+            // 3つ以上の円も、全て離す
+            let mut circles = [(0.0, 0.0, 10.0), (5.0, 0.0, 10.0), (0.0, 5.0, 10.0)];
+            separate_circles(&mut circles, 0.0);
+            for i in 0..circles.len() {
+                for j in (i + 1)..circles.len() {
+                    let (x_i, y_i, radius_i) = circles[i];
+                    let (x_j, y_j, radius_j) = circles[j];
+                    assert!((x_j - x_i).hypot(y_j - y_i) >= radius_i + radius_j - 1e-6);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,310 @@
+use svg::node::element::{Group, Rectangle, Text as TextElement};
+
+use crate::color::format_rgb;
+use crate::options::{LegendOptions, LegendPosition, LegendValue, NumberFormat, TextDirection};
+use crate::text::measure_text_width;
+use crate::typography::{apply_direction, apply_typography};
+
+/// 凡例の項目の間隔 (文字の大きさに対する比率)。
+const ITEM_SPACING_RATIO: f64 = 0.25;
+
+/// 項目を段組みした凡例。
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Legend<'a> {
+    options: &'a LegendOptions,
+    /// 項目の文字列と、見本の色。
+    items: Vec<(String, &'a str)>,
+    columns: usize,
+    rows: usize,
+    column_width: f64,
+}
+
+impl Legend<'_> {
+    /// 凡例の幅と高さ。
+    pub fn size(&self) -> (f64, f64) {
+        (
+            self.column_width * self.columns as f64 + self.column_gap() * (self.columns - 1) as f64,
+            self.item_height() * self.rows as f64 + self.row_gap() * (self.rows - 1) as f64,
+        )
+    }
+
+    fn item_height(&self) -> f64 {
+        let typography = &self.options.typography;
+        (typography.size as f64 * typography.line_height).max(self.options.swatch_size as f64)
+    }
+
+    fn swatch_gap(&self) -> f64 {
+        self.options.swatch_size as f64 * 0.5
+    }
+
+    fn column_gap(&self) -> f64 {
+        self.options.swatch_size as f64
+    }
+
+    fn row_gap(&self) -> f64 {
+        self.options.typography.size as f64 * ITEM_SPACING_RATIO
+    }
+
+    /// 凡例を置く側。右から左へ書くグラフでは、左右を反転する。
+    pub fn position(&self, is_chart_rtl: bool) -> LegendPosition {
+        match (self.options.position, is_chart_rtl) {
+            (LegendPosition::Left, true) => LegendPosition::Right,
+            (LegendPosition::Right, true) => LegendPosition::Left,
+            (position, _) => position,
+        }
+    }
+
+    /// 項目を縦に並べるかどうか。
+    fn is_column_major(&self) -> bool {
+        matches!(
+            self.options.position,
+            LegendPosition::Left | LegendPosition::Right
+        )
+    }
+
+    /// `origin`を左上として、凡例を作成する。
+    pub fn create(&self, origin: (f64, f64), is_chart_rtl: bool) -> Group {
+        let mut group = Group::new();
+        let swatch_size = self.options.swatch_size as f64;
+        let item_height = self.item_height();
+
+        for (i, (text, color)) in self.items.iter().enumerate() {
+            let (column, row) = if self.is_column_major() {
+                (i / self.rows, i % self.rows)
+            } else {
+                (i % self.columns, i / self.columns)
+            };
+            // 右から左へ書くグラフでは、列の順番と見本の位置を反転する
+            let column = if is_chart_rtl {
+                self.columns - 1 - column
+            } else {
+                column
+            };
+            let left = origin.0 + (self.column_width + self.column_gap()) * column as f64;
+            let center_y =
+                origin.1 + (item_height + self.row_gap()) * row as f64 + item_height * 0.5;
+
+            let (swatch_x, text_x) = if is_chart_rtl {
+                let right = left + self.column_width;
+                (right - swatch_size, right - swatch_size - self.swatch_gap())
+            } else {
+                (left, left + swatch_size + self.swatch_gap())
+            };
+            let swatch = Rectangle::new()
+                .set("x", swatch_x)
+                .set("y", center_y - swatch_size * 0.5)
+                .set("width", swatch_size)
+                .set("height", swatch_size)
+                .set("fill", *color);
+
+            let is_rtl = TextDirection::Auto.is_rtl(text);
+            let anchor = match (is_chart_rtl, is_rtl) {
+                (false, false) | (true, true) => "start",
+                _ => "end",
+            };
+            let element = TextElement::new(text.as_str())
+                .set("x", text_x)
+                .set("y", center_y)
+                .set("text-anchor", anchor)
+                .set("fill", format_rgb(self.options.color))
+                .set("dominant-baseline", "central");
+            let element = apply_typography(element, &self.options.typography);
+
+            group = group
+                .add(swatch)
+                .add(apply_direction(element, is_rtl, is_chart_rtl));
+        }
+
+        group
+    }
+}
+
+/// 凡例の項目を、`available`の幅と高さに合わせて段組みする。
+pub(crate) fn layout_legend<'a, T, R>(
+    pie_statuses: &'a [(T, f64, R)],
+    options: &'a LegendOptions,
+    total: Option<f64>,
+    (available_width, available_height): (f64, f64),
+) -> Legend<'a>
+where
+    T: AsRef<str>,
+    R: AsRef<str>,
+{
+    let items: Vec<(String, &str)> = pie_statuses
+        .iter()
+        .map(|(label, ratio, color)| {
+            (
+                format_item(label.as_ref(), *ratio, options, total),
+                color.as_ref(),
+            )
+        })
+        .collect();
+
+    let mut legend = Legend {
+        options,
+        items,
+        columns: 1,
+        rows: 1,
+        column_width: 0.0,
+    };
+    legend.column_width = legend
+        .items
+        .iter()
+        .map(|(text, _)| {
+            options.swatch_size as f64
+                + legend.swatch_gap()
+                + measure_text_width(text, &options.typography)
+        })
+        .fold(0.0, f64::max);
+
+    let count = legend.items.len().max(1);
+    let columns = match options.columns {
+        Some(columns) => columns as usize,
+        None if legend.is_column_major() => {
+            let max_rows = ((available_height + legend.row_gap())
+                / (legend.item_height() + legend.row_gap()))
+            .floor()
+            .max(1.0) as usize;
+            count.div_ceil(max_rows)
+        }
+        None => ((available_width + legend.column_gap())
+            / (legend.column_width + legend.column_gap()))
+        .floor() as usize,
+    };
+    legend.columns = columns.clamp(1, count);
+    legend.rows = count.div_ceil(legend.columns);
+
+    legend
+}
+
+/// 凡例の項目の文字列を作成する。
+pub(crate) fn format_item(
+    label: &str,
+    ratio: f64,
+    options: &LegendOptions,
+    total: Option<f64>,
+) -> String {
+    let value = match (&options.value, total) {
+        (None, _) => return label.to_string(),
+        (Some(LegendValue::Value(format)), Some(total)) => format.format(ratio * total),
+        // 合計が無い場合は、値の単位を付けない百分率
+        (Some(LegendValue::Value(format)), None) => format.to_percentage().format(ratio * 100.0),
+        (Some(LegendValue::Percentage(format)), _) => NumberFormat {
+            suffix: format!("%{}", format.suffix),
+            ..format.clone()
+        }
+        .format(ratio * 100.0),
+    };
+
+    if label.is_empty() {
+        value
+    } else {
+        format!("{label} {value}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod layout_legend {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            let case = [
+                ("Red", 0.5, "#fe5555"),
+                ("Green", 0.25, "#55fe55"),
+                ("Blue", 0.25, "#3366fe"),
+            ];
+            // 項目の高さ12、間隔2.5
+            let options = LegendOptions::default();
+
+            // 高さが足りない場合は、次の列へ折り返す
+            let legend = layout_legend(&case, &options, None, (100.0, 30.0));
+            assert_eq!((legend.columns, legend.rows), (2, 2));
+            // "Green"の幅30に、見本と間隔を加える
+            assert_eq!(legend.size(), (100.0, 26.5));
+
+            let options = LegendOptions {
+                position: LegendPosition::Bottom,
+                ..Default::default()
+            };
+            let legend = layout_legend(&case, &options, None, (100.0, 30.0));
+            assert_eq!((legend.columns, legend.rows), (2, 2));
+            let legend = layout_legend(&case, &options, None, (200.0, 30.0));
+            assert_eq!((legend.columns, legend.rows), (3, 1));
+
+            let options = LegendOptions {
+                columns: Some(3),
+                ..Default::default()
+            };
+            let legend = layout_legend(&case, &options, None, (100.0, 100.0));
+            assert_eq!((legend.columns, legend.rows), (3, 1));
+        }
+    }
+
+    mod create {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            let case = [("Red", 0.5, "#fe5555"), ("Blue", 0.5, "#3366fe")];
+            let options = LegendOptions {
+                position: LegendPosition::Bottom,
+                ..Default::default()
+            };
+            let legend = layout_legend(&case, &options, None, (200.0, 100.0));
+
+            let text = legend.create((10.0, 20.0), false).to_string();
+            assert!(
+                text.contains(r##"<rect fill="#fe5555" height="10" width="10" x="10" y="21"/>"##)
+            );
+            assert!(text.contains(r#"text-anchor="start" x="25" y="26">"#));
+            assert!(
+                text.contains(r##"<rect fill="#3366fe" height="10" width="10" x="59" y="21"/>"##)
+            );
+
+            // 右から左へ書くグラフでは、列の順番と見本の位置を反転する
+            let text = legend.create((10.0, 20.0), true).to_string();
+            assert!(
+                text.contains(r##"<rect fill="#fe5555" height="10" width="10" x="88" y="21"/>"##)
+            );
+            assert!(text.contains(r#"text-anchor="end" unicode-bidi="embed" x="83" y="26">"#));
+            assert!(
+                text.contains(r##"<rect fill="#3366fe" height="10" width="10" x="39" y="21"/>"##)
+            );
+        }
+    }
+
+    mod format_item {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            let mut options = LegendOptions::default();
+            assert_eq!(format_item("Red", 0.5, &options, Some(200.0)), "Red");
+
+            options.value = Some(LegendValue::Value(NumberFormat {
+                prefix: "$".to_string(),
+                ..Default::default()
+            }));
+            assert_eq!(
+                format_item("Red", 0.5, &options, Some(2000.0)),
+                "Red $1,000"
+            );
+            // 合計が無い場合は百分率
+            assert_eq!(format_item("Red", 0.5, &options, None), "Red 50%");
+
+            options.value = Some(LegendValue::Percentage(NumberFormat {
+                decimals: 1,
+                ..Default::default()
+            }));
+            assert_eq!(
+                format_item("Red", 0.125, &options, Some(2000.0)),
+                "Red 12.5%"
+            );
+            assert_eq!(format_item("", 0.125, &options, None), "12.5%");
+        }
+    }
+}
@@ -2,17 +2,59 @@
 //!
 //! This function returns pie chart made by `Document` of [`svg`].
 //!
+//! # Features
+//!
+//! - `font`: Loads local font files ([`font::Font`]) for measuring texts,
+//!   converting labels into outlined paths, and embedding subsetted fonts.
+//!
 //! [`svg`]: https://github.com/bodoni/svg
 use std::{f64::consts::FRAC_PI_2, f64::consts::TAU};
 
-use svg::node::element::Group;
-use util::normalize_angle;
+#[cfg(feature = "font")]
+use svg::node::element::Style;
+use svg::node::element::{Circle, Definitions, Group, Mask, Title};
+use svg::Node;
+use util::{calc_angle_coord, normalize_angle};
 
+#[cfg(feature = "font")]
+mod bidi;
+mod caption;
+mod category;
+mod center;
+mod color;
+#[cfg(feature = "font")]
+mod embed;
 pub mod error;
+#[cfg(feature = "font")]
+pub mod font;
+mod frame;
+mod id;
 mod label;
+mod layout;
+mod legend;
+mod multiple;
+pub mod options;
+#[cfg(feature = "font")]
+mod outline;
 mod pie;
+mod ruby;
+mod scatter;
+mod size;
+mod spark;
+#[cfg(feature = "font")]
+mod subset;
+mod text;
+mod typography;
 pub(crate) mod util;
 
+pub use layout::BoundingBox;
+pub use options::{
+    CategoryColors, CenterContent, CenterLine, CenterValue, ChartSize, ChartText, FrameOptions,
+    Insets, LabelColor, LabelHalo, LabelOptions, LabelOrientation, LabelOverflow, LabelThreshold,
+    LegendOptions, LegendPosition, LegendValue, NumberFormat, PhysicalUnit, PieChartOptions,
+    ProportionalPiesOptions, RubyOptions, ScatterPiesOptions, SmallMultiplesOptions,
+    SparkPieOptions, TextAlign, TextDirection, TotalAnnotation, Typography,
+};
 pub use svg::Document;
 
 /// Creates pie chart.
@@ -21,11 +63,15 @@ pub use svg::Document;
 ///
 /// The `width`, `height`, `circle_radius`, `label_size`, and `label_position_radius` are in pixels.
 /// The `label_color` is RGB ((0 ~ 255) * 3).
+/// The `label_font` is a font stack as a value of CSS `font-family`.
 ///
 /// The 1st of `pie_statuses` element is a label text.
 /// The 2nd of `pie_statuses` element is a ratio (0.0 ~ 1.0).
 /// The 3rd of `pie_statuses` element is a color of the pie (CSS style).
 ///
+/// The pie is centered at (`width / 2`, `height / 2`) and is not shrunk to fit in the canvas.
+/// The root `<svg>` has only `viewBox`, without `width` and `height`.
+/// Use [`create_pie_chart_with_options`] with [`PieChartOptions::size`] to set them.
 ///
 /// # Examples
 ///
@@ -55,6 +101,7 @@ pub use svg::Document;
 /// ```
 ///
 /// [`svg`]: https://github.com/bodoni/svg
+#[allow(clippy::too_many_arguments)]
 pub fn create_pie_chart<S, T, R>(
     width: u32,
     height: u32,
@@ -69,9 +116,363 @@ where
     S: AsRef<str>,
     T: AsRef<str>,
     R: AsRef<str>,
+{
+    // `font`フィーチャーが無効の場合、全てのフィールドが指定済みになる
+    #[allow(clippy::needless_update)]
+    let options = PieChartOptions {
+        circle_radius: Some(circle_radius),
+        label: LabelOptions {
+            color: label_color.into(),
+            typography: Typography {
+                font_family: label_font.as_ref().to_string(),
+                size: label_size,
+                ..Default::default()
+            },
+            position_radius: Some(label_position_radius),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    // 従来の出力を変えないよう、表示する大きさは設定しない
+    let mut document = Document::new().set("viewBox", format!("0, 0, {width}, {height}"));
+    append_chart(&mut document, width, height, pie_statuses, &options, true)?;
+
+    Ok(document)
+}
+
+/// Creates pie chart with [`PieChartOptions`].
+///
+/// This function returns pie chart made by `Document` of [`svg`].
+///
+/// The `width` and `height` are in pixels.
+///
+/// The 1st of `pie_statuses` element is a label text.
+/// The 2nd of `pie_statuses` element is a ratio (0.0 ~ 1.0).
+/// The 3rd of `pie_statuses` element is a color of the pie (CSS style).
+///
+/// # Examples
+///
+/// ```
+/// use svg_pie_chart::{create_pie_chart_with_options, LabelThreshold, PieChartOptions};
+///
+/// // (label, ratio, color)
+/// let case = [
+///     ("Red", 0.5, "#fe5555"),
+///     ("Green", 0.495, "#55fe55"),
+///     ("Tiny", 0.005, "#999"),
+/// ];
+///
+/// let mut options = PieChartOptions::default();
+/// // hides labels of pies smaller than 1%.
+/// options.label.hide_below = Some(LabelThreshold::Ratio(0.01));
+///
+/// let pie_chart = create_pie_chart_with_options(100, 100, &case, &options);
+///
+/// assert!(pie_chart.is_ok());
+/// ```
+///
+/// [`svg`]: https://github.com/bodoni/svg
+pub fn create_pie_chart_with_options<T, R>(
+    width: u32,
+    height: u32,
+    pie_statuses: &[(T, f64, R)],
+    options: &PieChartOptions,
+) -> Result<Document, error::PieChartError>
+where
+    T: AsRef<str>,
+    R: AsRef<str>,
 {
     let mut document = Document::new().set("viewBox", format!("0, 0, {width}, {height}"));
-    let circle_center = (width / 2, height / 2);
+    document = size::apply_size(document, width, height, &options.size);
+    append_chart(&mut document, width, height, pie_statuses, options, false)?;
+
+    Ok(document)
+}
+
+/// Creates pie chart as `Group` of [`svg`], to be placed in another document.
+///
+/// This function returns the group and its bounding box,
+/// which is from `(0, 0)` to (`width`, `height`) in pixels.
+/// The group can be positioned with `transform`, e.g. `translate(x, y)`.
+///
+/// The arguments are the same as [`create_pie_chart_with_options`].
+/// Ids in the group are scoped with [`PieChartOptions::id_prefix`],
+/// so that the group can be placed with other charts.
+///
+/// # Examples
+///
+/// ```
+/// use svg_pie_chart::{create_pie_chart_group, Document, PieChartOptions};
+///
+/// let case = [("Red", 0.5, "#fe5555"), ("Blue", 0.5, "#3366fe")];
+///
+/// let (group, bounding_box) =
+///     create_pie_chart_group(100, 100, &case, &PieChartOptions::default()).unwrap();
+/// let dashboard = Document::new()
+///     .set("viewBox", (0, 0, 300, 100))
+///     .add(group.set("transform", "translate(200, 0)"));
+///
+/// assert_eq!(bounding_box.width, 100.0);
+/// ```
+///
+/// [`svg`]: https://github.com/bodoni/svg
+pub fn create_pie_chart_group<T, R>(
+    width: u32,
+    height: u32,
+    pie_statuses: &[(T, f64, R)],
+    options: &PieChartOptions,
+) -> Result<(Group, BoundingBox), error::PieChartError>
+where
+    T: AsRef<str>,
+    R: AsRef<str>,
+{
+    let mut group = Group::new();
+    append_chart(&mut group, width, height, pie_statuses, options, false)?;
+
+    Ok((group, BoundingBox::from_size(width, height)))
+}
+
+/// Creates small multiples, a grid of pie charts of several datasets.
+///
+/// The 1st of `datasets` element is a name of the dataset, shown as the title of the cell.
+/// The 2nd of `datasets` element is a list of a category and a ratio (0.0 ~ 1.0).
+///
+/// Each category has the same color in all the cells (see [`CategoryColors`]),
+/// and a single legend of the categories is drawn.
+/// The `width` and `height` of the whole grid are in pixels.
+///
+/// # Examples
+///
+/// ```
+/// use svg_pie_chart::{create_small_multiples, SmallMultiplesOptions};
+///
+/// let datasets = [
+///     ("North", vec![("Red", 0.5), ("Green", 0.5)]),
+///     ("South", vec![("Red", 0.25), ("Blue", 0.75)]),
+/// ];
+///
+/// let options = SmallMultiplesOptions {
+///     columns: 2,
+///     ..Default::default()
+/// };
+/// let pie_charts = create_small_multiples(300, 120, &datasets, &options);
+///
+/// assert!(pie_charts.is_ok());
+/// ```
+pub fn create_small_multiples<N, D, T>(
+    width: u32,
+    height: u32,
+    datasets: &[(N, D)],
+    options: &SmallMultiplesOptions,
+) -> Result<Document, error::PieChartError>
+where
+    N: AsRef<str>,
+    D: AsRef<[(T, f64)]>,
+    T: AsRef<str>,
+{
+    multiple::create_small_multiples(width, height, datasets, options)
+}
+
+/// Creates pie charts side by side, whose areas are proportional to their totals.
+///
+/// The 1st of `datasets` element is a name of the dataset, shown as the title of the pie.
+/// The 2nd of `datasets` element is a total of the dataset, e.g. revenue.
+/// The 3rd of `datasets` element is a list of a category and a ratio (0.0 ~ 1.0).
+///
+/// The pie of [`ProportionalPiesOptions::reference_total`] has the largest radius
+/// fitting in every cell, and the others are scaled, so that a pie twice the area
+/// means twice the total.
+/// Each category has the same color in all the pies (see [`CategoryColors`]).
+///
+/// # Examples
+///
+/// ```
+/// use svg_pie_chart::{create_proportional_pies, ProportionalPiesOptions};
+///
+/// let datasets = [
+///     ("2024", 1200.0, vec![("Red", 0.5), ("Green", 0.5)]),
+///     ("2025", 2400.0, vec![("Red", 0.25), ("Green", 0.75)]),
+/// ];
+///
+/// let pie_charts =
+///     create_proportional_pies(300, 150, &datasets, &ProportionalPiesOptions::default());
+///
+/// assert!(pie_charts.is_ok());
+/// ```
+pub fn create_proportional_pies<N, D, T>(
+    width: u32,
+    height: u32,
+    datasets: &[(N, f64, D)],
+    options: &ProportionalPiesOptions,
+) -> Result<Document, error::PieChartError>
+where
+    N: AsRef<str>,
+    D: AsRef<[(T, f64)]>,
+    T: AsRef<str>,
+{
+    multiple::create_proportional_pies(width, height, datasets, options)
+}
+
+/// Creates pie charts without labels at arbitrary coordinates, e.g. markers on a map.
+///
+/// The 1st and 2nd of `markers` element are the x and y coordinates of the center.
+/// The 3rd of `markers` element is a radius of the pie.
+/// The 4th of `markers` element is a list of a category and a ratio (0.0 ~ 1.0).
+///
+/// Each category has the same color in all the pies (see [`CategoryColors`]).
+/// The returned `Group` can be placed on a map or a scatter plot of [`svg`].
+///
+/// # Examples
+///
+/// ```
+/// use svg_pie_chart::{create_scatter_pies, Document, ScatterPiesOptions};
+///
+/// let markers = [
+///     (120.0, 80.0, 12, vec![("Red", 0.5), ("Green", 0.5)]),
+///     (200.0, 150.0, 8, vec![("Red", 0.25), ("Blue", 0.75)]),
+/// ];
+///
+/// let options = ScatterPiesOptions {
+///     avoid_overlap: Some(2),
+///     ..Default::default()
+/// };
+/// let group = create_scatter_pies(&markers, &options).unwrap();
+/// let map = Document::new().set("viewBox", (0, 0, 300, 200)).add(group);
+/// ```
+///
+/// [`svg`]: https://github.com/bodoni/svg
+pub fn create_scatter_pies<D, T>(
+    markers: &[(f64, f64, u32, D)],
+    options: &ScatterPiesOptions,
+) -> Result<Group, error::PieChartError>
+where
+    D: AsRef<[(T, f64)]>,
+    T: AsRef<str>,
+{
+    scatter::create_scatter_pies(markers, options)
+}
+
+/// Creates a tiny pie chart of a single ratio, e.g. a completion ratio in a table cell.
+///
+/// `size` is the width and height in pixels, and `ratio` is 0.0 ~ 1.0.
+///
+/// The chart has no labels, clip paths nor ids, and draws the ratio
+/// with `stroke-dasharray` of a single circle over a background ring,
+/// so that thousands of them can be embedded in a page.
+///
+/// # Examples
+///
+/// ```
+/// use svg_pie_chart::{create_spark_pie, SparkPieOptions};
+///
+/// let spark_pie = create_spark_pie(16, 0.75, &SparkPieOptions::default());
+///
+/// assert!(spark_pie.to_string().contains(r#"stroke-dasharray="75 100""#));
+/// ```
+pub fn create_spark_pie(size: u32, ratio: f64, options: &SparkPieOptions) -> Document {
+    spark::create_spark_pie(size, ratio, options)
+}
+
+/// `target`に、グラフの要素を加える。
+///
+/// `is_legacy`の場合、従来の[`create_pie_chart`]と同じく、
+/// 中心を`(width / 2, height / 2)`とし、指定された半径を縮めない。
+fn append_chart<N, T, R>(
+    target: &mut N,
+    width: u32,
+    height: u32,
+    pie_statuses: &[(T, f64, R)],
+    options: &PieChartOptions,
+    is_legacy: bool,
+) -> Result<(), error::PieChartError>
+where
+    N: Node,
+    T: AsRef<str>,
+    R: AsRef<str>,
+{
+    let is_chart_rtl = is_chart_rtl(pie_statuses, options.direction);
+    if is_chart_rtl {
+        target.assign("direction", "rtl");
+    }
+
+    let ratio_sum: f64 = pie_statuses.iter().map(|(_, ratio, _)| ratio).sum();
+
+    let id_prefix = match &options.id_prefix {
+        Some(id_prefix) => {
+            id::validate_id_prefix(id_prefix)?;
+            id_prefix.clone()
+        }
+        None => id::create_id_prefix(&describe_chart(width, height, pie_statuses, options)),
+    };
+
+    #[cfg(feature = "font")]
+    let embedded_options = embed_fonts(pie_statuses, options, ratio_sum, &id_prefix)?;
+    #[cfg(feature = "font")]
+    let options = match &embedded_options {
+        Some((options, style)) => {
+            target.append(Definitions::new().add(Style::new(style.as_str())));
+            options
+        }
+        None => options,
+    };
+
+    let (frame_area, content_area, header, footer) = layout_texts(width, height, options);
+    let mut plot_area = layout::calc_plot_area(
+        &content_area,
+        caption::calc_blocks_height(&header),
+        caption::calc_blocks_height(&footer),
+    );
+    let footer_top = plot_area.bottom + layout::SPACING;
+
+    // 凡例の分を除いた範囲に、円を縮めて置く
+    let legend = options.legend.as_ref().map(|legend_options| {
+        let legend = legend::layout_legend(
+            pie_statuses,
+            legend_options,
+            options.total,
+            (
+                plot_area.width() - layout::SPACING * 2.0,
+                plot_area.height() - layout::SPACING * 2.0,
+            ),
+        );
+        let origin =
+            layout::place_legend(&mut plot_area, legend.position(is_chart_rtl), legend.size());
+        (legend, origin)
+    });
+
+    let mut label_options = resolve_label_options(width, height, options);
+
+    // 半径を指定しない場合は、ラベルまで収まる最大の半径にする
+    let circle_radius = match options.circle_radius {
+        Some(circle_radius) => circle_radius,
+        None => fit_radius(&plot_area, pie_statuses, &label_options),
+    };
+    let (circle_center, scale) = if is_legacy {
+        ((width / 2, height / 2), 1.0)
+    } else {
+        layout::fit_circle(&plot_area, circle_radius)
+    };
+    let circle_radius = scale_length(circle_radius, scale);
+    let inner_radius = scale_length(options.inner_radius, scale);
+    let position_radius = match options.label.position_radius {
+        Some(position_radius) => scale_length(position_radius, scale),
+        None => (circle_radius as f64 * options.label.position_ratio).round() as u32,
+    };
+    label_options.position_radius = Some(position_radius);
+
+    if let Some(title) = options
+        .title
+        .as_ref()
+        .filter(|title| !title.text.is_empty())
+    {
+        target.append(Title::new(title.text.as_str()));
+    }
+    if let Some(frame) =
+        frame::create_frame(&frame_area, options.background, options.frame.as_ref())
+    {
+        target.append(frame);
+    }
 
     let mut pie_group = Group::new();
     let mut label_group = Group::new();
@@ -91,29 +492,330 @@ where
             center_angle,
             target_angle_range,
             pie_color.as_ref(),
-            &format!("p_{i}"),
+            &format!("{id_prefix}p_{i}"),
         )?;
         pie_group = pie_group.add(pie);
 
         let label = label::crate_label(
             circle_center,
-            label_color,
-            &label_font,
-            label_size,
-            label_position_radius,
+            &label_options,
             center_angle,
             target_angle_range,
             label.as_ref(),
-        );
+            pie_color.as_ref(),
+            &format!("{id_prefix}l_{i}"),
+            is_chart_rtl,
+        )?;
         label_group = label_group.add(label);
 
         base_angle -= target_angle_range;
         base_angle = normalize_angle(base_angle);
     }
 
-    document = document.add(pie_group).add(label_group);
+    // ドーナツグラフの場合、マスクで穴を開ける
+    if inner_radius > 0 {
+        let mask_id = format!("{id_prefix}donut-hole");
+        let mask = Mask::new()
+            .set("id", mask_id.as_str())
+            .add(
+                Circle::new()
+                    .set("cx", circle_center.0)
+                    .set("cy", circle_center.1)
+                    .set("r", circle_radius)
+                    .set("fill", "white"),
+            )
+            .add(
+                Circle::new()
+                    .set("cx", circle_center.0)
+                    .set("cy", circle_center.1)
+                    .set("r", inner_radius)
+                    .set("fill", "black"),
+            );
+        target.append(Definitions::new().add(mask));
+        pie_group = pie_group.set("mask", format!("url(#{mask_id})"));
+    }
 
-    Ok(document)
+    target.append(pie_group);
+    target.append(label_group);
+
+    if let Some((legend, origin)) = &legend {
+        target.append(legend.create(*origin, is_chart_rtl));
+    }
+
+    let text_edges = (
+        content_area.left + layout::SPACING,
+        content_area.right - layout::SPACING,
+    );
+    if !header.is_empty() {
+        target.append(caption::create_blocks(
+            &header,
+            content_area.top + layout::SPACING,
+            text_edges,
+            is_chart_rtl,
+        ));
+    }
+    if !footer.is_empty() {
+        target.append(caption::create_blocks(
+            &footer,
+            footer_top,
+            text_edges,
+            is_chart_rtl,
+        ));
+    }
+
+    if let Some(center) = &options.center {
+        target.append(center::create_center(
+            circle_center,
+            inner_radius,
+            center,
+            options.total,
+            ratio_sum,
+            is_chart_rtl,
+        ));
+    }
+
+    Ok(())
+}
+
+/// 余白、枠と内側の余白を除いた範囲に、見出しと出典を配置する。
+///
+/// 背景と枠を描く範囲、中身を置く範囲、見出し、出典を返す。
+fn layout_texts(
+    width: u32,
+    height: u32,
+    options: &PieChartOptions,
+) -> (
+    layout::Area,
+    layout::Area,
+    Vec<caption::TextBlock<'_>>,
+    Vec<caption::TextBlock<'_>>,
+) {
+    // 余白の内側に背景と枠を描き、枠と内側の余白を除いた範囲に中身を置く
+    let frame_area = layout::Area::from_size(width, height).shrink(&options.margin, 0.0);
+    let frame_width = options.frame.map_or(0.0, |frame| frame.width);
+    let content_area = frame_area.shrink(&options.padding, frame_width);
+
+    let text_width = content_area.width() - layout::SPACING * 2.0;
+    let header = caption::layout_texts(
+        options.title.iter().chain(options.subtitle.as_ref()),
+        text_width,
+    );
+    let footer = caption::layout_texts(options.source.as_ref(), text_width);
+
+    (frame_area, content_area, header, footer)
+}
+
+/// ラベルの大きさを、グラフの大きさに合わせて解決する。
+fn resolve_label_options(width: u32, height: u32, options: &PieChartOptions) -> LabelOptions {
+    let mut label_options = options.label.clone();
+    if let Some(relative_size) = options.label.relative_size {
+        label_options.typography.size =
+            ((width.min(height) as f64 * relative_size).round() as u32).max(1);
+    }
+
+    label_options
+}
+
+/// `plot_area`に円とラベルが収まる、最大の半径を計算する。
+fn fit_radius<T, R>(
+    plot_area: &layout::Area,
+    pie_statuses: &[(T, f64, R)],
+    label_options: &LabelOptions,
+) -> u32
+where
+    T: AsRef<str>,
+{
+    layout::fit_radius(
+        plot_area,
+        &calc_label_extents(pie_statuses, label_options),
+        label_options
+            .position_radius
+            .is_none()
+            .then_some(label_options.position_ratio),
+    )
+}
+
+/// 凡例を置かない場合に、円とラベルがグラフに収まる最大の半径を計算する。
+fn fit_chart_radius<T, R>(
+    width: u32,
+    height: u32,
+    pie_statuses: &[(T, f64, R)],
+    options: &PieChartOptions,
+) -> u32
+where
+    T: AsRef<str>,
+{
+    let (_, content_area, header, footer) = layout_texts(width, height, options);
+    let plot_area = layout::calc_plot_area(
+        &content_area,
+        caption::calc_blocks_height(&header),
+        caption::calc_blocks_height(&footer),
+    );
+
+    fit_radius(
+        &plot_area,
+        pie_statuses,
+        &resolve_label_options(width, height, options),
+    )
+}
+
+/// 長さを`scale`倍にする。
+fn scale_length(length: u32, scale: f64) -> u32 {
+    (length as f64 * scale).floor() as u32
+}
+
+/// 表示する各ラベルの中心の方向と、中心から端までの幅と高さを見積もる。
+fn calc_label_extents<T, R>(
+    pie_statuses: &[(T, f64, R)],
+    options: &LabelOptions,
+) -> Vec<layout::LabelExtent>
+where
+    T: AsRef<str>,
+{
+    let mut extents = Vec::new();
+    let mut base_angle = FRAC_PI_2;
+    for (label, ratio, _) in pie_statuses {
+        let target_angle_range = TAU * ratio;
+        let center_angle = base_angle - (target_angle_range * 0.5);
+        base_angle -= target_angle_range;
+
+        if options
+            .hide_below
+            .is_some_and(|threshold| threshold.is_below(target_angle_range))
+        {
+            continue;
+        }
+        extents.push((
+            calc_angle_coord(center_angle),
+            label::measure_label_extent(label.as_ref(), options),
+        ));
+    }
+
+    extents
+}
+
+/// idの接頭辞を作成するための、グラフの内容を表す文字列を作成する。
+fn describe_chart<T, R>(
+    width: u32,
+    height: u32,
+    pie_statuses: &[(T, f64, R)],
+    options: &PieChartOptions,
+) -> String
+where
+    T: AsRef<str>,
+    R: AsRef<str>,
+{
+    let mut description = format!("{width}\0{height}\0");
+    for (label, ratio, pie_color) in pie_statuses {
+        description.push_str(&format!(
+            "{}\0{ratio:?}\0{}\0",
+            label.as_ref(),
+            pie_color.as_ref()
+        ));
+    }
+    description.push_str(&format!("{options:?}"));
+
+    description
+}
+
+/// グラフを右から左へ書くかどうか。
+///
+/// 自動の場合は、強い方向性を持つ文字のある最初のラベルに従う。
+fn is_chart_rtl<T, R>(pie_statuses: &[(T, f64, R)], direction: TextDirection) -> bool
+where
+    T: AsRef<str>,
+{
+    let labels: String = pie_statuses
+        .iter()
+        .map(|(label, _, _)| label.as_ref())
+        .collect::<Vec<_>>()
+        .join(" ");
+    direction.is_rtl(&labels)
+}
+
+/// 埋め込むフォントの`@font-face`と、埋め込んだフォントを使うオプションを作成する。
+///
+/// 埋め込むフォントが無い場合は`None`を返す。
+#[cfg(feature = "font")]
+fn embed_fonts<T, R>(
+    pie_statuses: &[(T, f64, R)],
+    options: &PieChartOptions,
+    ratio_sum: f64,
+    id_prefix: &str,
+) -> Result<Option<(PieChartOptions, String)>, error::PieChartError>
+where
+    T: AsRef<str>,
+{
+    if !options.embed_fonts {
+        return Ok(None);
+    }
+
+    let mut texts = Vec::new();
+    // 輪郭に変換したラベルはフォントを必要としない
+    if !options.label.outline {
+        // 省略記号付きで切り詰められることがあるため、省略記号も含める
+        let text: String = pie_statuses
+            .iter()
+            .map(|(label, _, _)| label.as_ref())
+            .chain(["…"])
+            .collect();
+        texts.push((&options.label.typography, text));
+    }
+    if let Some(center) = &options.center {
+        for line in std::iter::once(&center.primary).chain(center.secondary.as_ref()) {
+            let text = center::format_value(&line.value, options.total, ratio_sum);
+            texts.push((&line.typography, text));
+        }
+    }
+    if let Some(legend) = &options.legend {
+        let text: String = pie_statuses
+            .iter()
+            .map(|(label, ratio, _)| {
+                legend::format_item(label.as_ref(), *ratio, legend, options.total)
+            })
+            .collect();
+        texts.push((&legend.typography, text));
+    }
+    for text in [&options.title, &options.subtitle, &options.source]
+        .into_iter()
+        .flatten()
+    {
+        texts.push((&text.typography, text.text.clone()));
+    }
+
+    let embeddings = embed::collect_fonts(texts, id_prefix);
+    if embeddings.is_empty() {
+        return Ok(None);
+    }
+
+    let mut font_faces = String::new();
+    let mut embedded_options = options.clone();
+    for embedding in embeddings {
+        font_faces.push_str(&embedding.create_font_face()?);
+
+        embedding.apply(&mut embedded_options.label.typography);
+        if let Some(center) = &mut embedded_options.center {
+            embedding.apply(&mut center.primary.typography);
+            if let Some(secondary) = &mut center.secondary {
+                embedding.apply(&mut secondary.typography);
+            }
+        }
+        if let Some(legend) = &mut embedded_options.legend {
+            embedding.apply(&mut legend.typography);
+        }
+        for text in [
+            &mut embedded_options.title,
+            &mut embedded_options.subtitle,
+            &mut embedded_options.source,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            embedding.apply(&mut text.typography);
+        }
+    }
+
+    Ok(Some((embedded_options, font_faces)))
 }
 
 #[cfg(test)]
@@ -160,5 +862,459 @@ mod tests {
                 .join("test_target/test_create_pie_chart_002.svg");
             svg::save(path, &document).unwrap();
         }
+
+        #[test]
+        fn success_when_larger_than_canvas() {
+            let case = vec![("Red", 0.5, "#fe5555"), ("Blue", 0.5, "#3366fe")];
+
+            let document =
+                create_pie_chart(101, 101, 60, (0, 0, 0), "sans-serif", 10, 120, &case).unwrap();
+            let text = document.to_string();
+            // 従来どおり、表示する大きさは設定しない
+            assert!(text.starts_with(
+                r#"<svg viewBox="0, 0, 101, 101" xmlns="http://www.w3.org/2000/svg">"#
+            ));
+            // 従来どおり、中心は切り捨て、指定された半径とラベルの位置は縮めない
+            assert!(text.contains(r##"cx="50" cy="50" fill="#fe5555" r="60""##));
+            assert!(text.contains(r#"x="170""#));
+        }
+
+        #[test]
+        fn success_when_hiding_small_labels() {
+            let case = vec![
+                ("Red", 0.5, "#fe5555"),
+                ("Green", 0.49, "#55fe55"),
+                ("Tiny", 0.005, "#3366fe"),
+                ("Other", 0.005, "#999"),
+            ];
+            let options = PieChartOptions {
+                label: LabelOptions {
+                    hide_below: Some(LabelThreshold::Ratio(0.01)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+
+            let document = create_pie_chart_with_options(100, 100, &case, &options).unwrap();
+            let text = document.to_string();
+            assert!(text.contains("Green"));
+            assert!(!text.contains("Tiny"));
+            assert!(!text.contains("Other"));
+
+            let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("test_target/test_create_pie_chart_hide-small-labels.svg");
+            svg::save(path, &document).unwrap();
+        }
+
+        #[test]
+        fn success_when_donut_with_center() {
+            let case = vec![
+                ("Red", 0.5, "#fe5555"),
+                ("Green", 0.25, "#55fe55"),
+                ("Blue", 0.25, "#3366fe"),
+            ];
+            let options = PieChartOptions {
+                inner_radius: 25,
+                total: Some(1234.0),
+                center: Some(CenterContent {
+                    secondary: Some(CenterLine {
+                        value: CenterValue::Text("users".to_string()),
+                        typography: Typography {
+                            size: 8,
+                            ..Default::default()
+                        },
+                        color: (96, 96, 96),
+                    }),
+                    ..Default::default()
+                }),
+                label: LabelOptions {
+                    position_radius: Some(32),
+                    ..Default::default()
+                },
+                id_prefix: Some("donut-".to_string()),
+                ..Default::default()
+            };
+
+            let document = create_pie_chart_with_options(100, 100, &case, &options).unwrap();
+            let text = document.to_string();
+            assert!(text.contains(r#"<mask id="donut-donut-hole">"#));
+            assert!(text.contains(r#"<g mask="url(#donut-donut-hole)">"#));
+            assert!(text.contains("1,234"));
+            assert!(text.contains("users"));
+
+            let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("test_target/test_create_pie_chart_donut.svg");
+            svg::save(path, &document).unwrap();
+        }
+
+        #[test]
+        fn success_when_rtl() {
+            let case = vec![
+                ("אדום", 0.5, "#fe5555"),
+                ("ירוק (20%)", 0.2, "#55fe55"),
+                ("Other", 0.3, "#999"),
+            ];
+
+            let document =
+                create_pie_chart_with_options(100, 100, &case, &PieChartOptions::default())
+                    .unwrap();
+            let text = document.to_string();
+            assert!(text.starts_with(r#"<svg direction="rtl""#));
+            assert!(text.contains(r#"direction="ltr""#));
+
+            let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("test_target/test_create_pie_chart_rtl.svg");
+            svg::save(path, &document).unwrap();
+        }
+
+        #[cfg(feature = "font")]
+        #[test]
+        fn success_when_embedding_fonts() {
+            use crate::font::{tests::create_test_font, Font};
+
+            let case = vec![("AB", 0.5, "#fe5555"), ("東", 0.5, "#55fe55")];
+            let options = PieChartOptions {
+                label: LabelOptions {
+                    typography: Typography {
+                        font_family: "Test Font".to_string(),
+                        font: Some(Font::from_bytes(create_test_font()).unwrap()),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                id_prefix: Some("chart-".to_string()),
+                embed_fonts: true,
+                ..Default::default()
+            };
+
+            let document = create_pie_chart_with_options(100, 100, &case, &options).unwrap();
+            let text = document.to_string();
+            assert!(text.contains("@font-face{font-family:'chart-svg-pie-chart-0';"));
+            assert!(text.contains("font-family=\"&apos;chart-svg-pie-chart-0&apos;, Test Font\""));
+
+            let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("test_target/test_create_pie_chart_embed-fonts.svg");
+            svg::save(path, &document).unwrap();
+        }
+
+        #[test]
+        fn success_when_title_and_source() {
+            let case = vec![
+                ("Red", 0.5, "#fe5555"),
+                ("Green", 0.25, "#55fe55"),
+                ("Blue", 0.25, "#3366fe"),
+            ];
+            let title_typography = Typography {
+                size: 14,
+                weight: Some("bold".to_string()),
+                ..Default::default()
+            };
+            let options = PieChartOptions {
+                title: Some(ChartText {
+                    text: "Sales by color".to_string(),
+                    typography: title_typography,
+                    ..Default::default()
+                }),
+                subtitle: Some(ChartText {
+                    text: "Fiscal year 2026".to_string(),
+                    color: (96, 96, 96),
+                    ..Default::default()
+                }),
+                source: Some(ChartText {
+                    text: "Source: internal survey".to_string(),
+                    typography: Typography {
+                        size: 8,
+                        ..Default::default()
+                    },
+                    align: TextAlign::End,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            };
+
+            let document = create_pie_chart_with_options(200, 100, &case, &options).unwrap();
+            let text = document.to_string();
+            assert!(text.contains("<title>Sales by color</title>"));
+            // 見出しと出典の間に収まるよう、円を縮める
+            assert!(text.contains(r##"cx="100" cy="60" fill="#fe5555" r="22""##));
+            assert!(text.contains(r#"text-anchor="end" x="196" y="#));
+
+            let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("test_target/test_create_pie_chart_title.svg");
+            svg::save(path, &document).unwrap();
+        }
+
+        #[test]
+        fn success_when_legend() {
+            let case = vec![
+                ("Red", 0.5, "#fe5555"),
+                ("Green", 0.10, "#55fe55"),
+                ("Blue", 0.25, "#3366fe"),
+                ("Other", 0.15, "#999"),
+            ];
+            let options = PieChartOptions {
+                legend: Some(LegendOptions {
+                    value: Some(LegendValue::Percentage(NumberFormat::default())),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            };
+
+            let document = create_pie_chart_with_options(200, 100, &case, &options).unwrap();
+            let text = document.to_string();
+            // 凡例の分、円を左へ寄せる
+            assert!(text.contains(r##"cx="62" cy="50" fill="#fe5555" r="40""##));
+            assert!(
+                text.contains(r##"<rect fill="#999" height="10" width="10" x="127" y="66.75"/>"##)
+            );
+            assert!(text.contains("Other 15%"));
+
+            let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("test_target/test_create_pie_chart_legend.svg");
+            svg::save(path, &document).unwrap();
+        }
+
+        #[test]
+        fn success_when_auto_layout() {
+            let case = vec![
+                ("Red", 0.5, "#fe5555"),
+                ("Green", 0.10, "#55fe55"),
+                ("Blue", 0.25, "#3366fe"),
+                ("Other", 0.15, "#999"),
+            ];
+            let options = PieChartOptions {
+                circle_radius: None,
+                margin: Insets::uniform(10),
+                label: LabelOptions {
+                    position_radius: None,
+                    position_ratio: 1.25,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+
+            let document = create_pie_chart_with_options(200, 120, &case, &options).unwrap();
+            let text = document.to_string();
+            // 余白を除いた高さ100に、外側のラベルまで収まる半径
+            assert!(text.contains(r##"cx="100" cy="60" fill="#fe5555" r="37""##));
+            assert!(text.contains(r#"x="146" y="60""#));
+
+            let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("test_target/test_create_pie_chart_auto-layout.svg");
+            svg::save(path, &document).unwrap();
+        }
+
+        #[test]
+        fn success_when_background_and_frame() {
+            let case = vec![("Red", 0.5, "#fe5555"), ("Blue", 0.5, "#3366fe")];
+            let options = PieChartOptions {
+                circle_radius: None,
+                margin: Insets::uniform(4),
+                padding: Insets::uniform(8),
+                background: Some((255, 250, 240)),
+                frame: Some(FrameOptions {
+                    color: (128, 128, 128),
+                    width: 2.0,
+                    corner_radius: 10.0,
+                }),
+                ..Default::default()
+            };
+
+            let document = create_pie_chart_with_options(100, 100, &case, &options).unwrap();
+            let text = document.to_string();
+            assert!(text.contains(r#"<rect fill="rgb(255, 250, 240)" height="90" rx="10" stroke="rgb(128, 128, 128)" stroke-width="2" width="90" x="5" y="5"/>"#));
+            // 余白、枠、内側の余白を除いた範囲に収める
+            assert!(text.contains(r##"cx="50" cy="50" fill="#fe5555" r="36""##));
+
+            let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("test_target/test_create_pie_chart_frame.svg");
+            svg::save(path, &document).unwrap();
+        }
+
+        #[test]
+        fn success_when_physical_size() {
+            let case = vec![("Red", 0.5, "#fe5555"), ("Blue", 0.5, "#3366fe")];
+            let options = PieChartOptions {
+                size: ChartSize::Physical {
+                    unit: PhysicalUnit::Millimeter,
+                    dpi: 254.0,
+                },
+                label: LabelOptions {
+                    relative_size: Some(0.05),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+
+            let document = create_pie_chart_with_options(400, 200, &case, &options).unwrap();
+            let text = document.to_string();
+            // viewBoxはピクセルのまま
+            assert!(text.starts_with(r#"<svg height="20mm" viewBox="0, 0, 400, 200" width="40mm""#));
+            assert!(text.contains(r#"font-size="10""#));
+
+            let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("test_target/test_create_pie_chart_physical-size.svg");
+            svg::save(path, &document).unwrap();
+        }
+
+        #[test]
+        fn failure_when_invalid_id_prefix() {
+            let case = vec![("Red", 0.5, "#fe5555"), ("Blue", 0.5, "#3366fe")];
+            for id_prefix in ["my chart", "chart)", "chart#"] {
+                let options = PieChartOptions {
+                    id_prefix: Some(id_prefix.to_string()),
+                    ..Default::default()
+                };
+                assert_eq!(
+                    create_pie_chart_with_options(100, 100, &case, &options).unwrap_err(),
+                    error::PieChartError::InvalidIdPrefix(id_prefix.to_string())
+                );
+            }
+        }
+
+        #[test]
+        fn success_when_two_charts_in_one_page() {
+            let case = vec![
+                ("Red", 0.5, "#fe5555"),
+                ("Green", 0.25, "#55fe55"),
+                ("Blue", 0.25, "#3366fe"),
+            ];
+            let other_case = vec![("Red", 0.75, "#fe5555"), ("Blue", 0.25, "#3366fe")];
+            let options = PieChartOptions {
+                inner_radius: 20,
+                label: LabelOptions {
+                    orientation: LabelOrientation::Curved,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+
+            let charts = [
+                create_pie_chart_with_options(100, 100, &case, &options).unwrap(),
+                create_pie_chart_with_options(100, 100, &other_case, &options).unwrap(),
+            ];
+            let html = format!(
+                "<!DOCTYPE html>\n<html>\n<body>\n{}\n{}\n</body>\n</html>\n",
+                charts[0], charts[1]
+            );
+
+            let ids: Vec<&str> = html
+                .split(" id=\"")
+                .skip(1)
+                .map(|rest| rest.split('"').next().unwrap())
+                .collect();
+            let mut unique_ids = ids.clone();
+            unique_ids.sort();
+            unique_ids.dedup();
+            assert_eq!(ids.len(), unique_ids.len());
+
+            // 全ての参照が、同じグラフの中のidを指す
+            for chart in &charts {
+                let chart = chart.to_string();
+                for reference in chart.split("url(#").skip(1) {
+                    let id = reference.split(')').next().unwrap();
+                    assert!(chart.contains(&format!(" id=\"{id}\"")));
+                }
+                for reference in chart.split("href=\"#").skip(1) {
+                    let id = reference.split('"').next().unwrap();
+                    assert!(chart.contains(&format!(" id=\"{id}\"")));
+                }
+            }
+
+            // 接頭辞を指定する
+            let options = PieChartOptions {
+                id_prefix: Some("sales-".to_string()),
+                ..options
+            };
+            let text = create_pie_chart_with_options(100, 100, &case, &options)
+                .unwrap()
+                .to_string();
+            assert!(text.contains(r#"<clipPath id="sales-p_0">"#));
+            assert!(text.contains(r#"mask="url(#sales-donut-hole)""#));
+
+            let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("test_target/test_create_pie_chart_two-charts.html");
+            std::fs::write(path, html).unwrap();
+        }
+    }
+
+    #[cfg(feature = "font")]
+    mod describe_chart {
+        use super::*;
+
+        #[test]
+        fn checking_value_when_fonts_differ() {
+            use crate::font::{tests::create_test_font, Font};
+
+            // 長さが同じで、内容だけが異なるフォント
+            let create_options = |last_byte| {
+                let mut data = create_test_font();
+                data.push(last_byte);
+                PieChartOptions {
+                    label: LabelOptions {
+                        typography: Typography {
+                            font: Some(Font::from_bytes(data).unwrap()),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                }
+            };
+            let case = vec![("Red", 0.5, "#fe5555"), ("Blue", 0.5, "#3366fe")];
+
+            assert_eq!(
+                describe_chart(100, 100, &case, &create_options(0)),
+                describe_chart(100, 100, &case, &create_options(0))
+            );
+            assert_ne!(
+                describe_chart(100, 100, &case, &create_options(0)),
+                describe_chart(100, 100, &case, &create_options(1))
+            );
+        }
+    }
+
+    mod create_pie_chart_group {
+        use std::path::PathBuf;
+
+        use super::*;
+
+        #[test]
+        fn success_when_placed_in_document() {
+            let case = vec![("Red", 0.5, "#fe5555"), ("Blue", 0.5, "#3366fe")];
+            let options = PieChartOptions {
+                id_prefix: Some("left-".to_string()),
+                ..Default::default()
+            };
+            let (left, bounding_box) = create_pie_chart_group(100, 100, &case, &options).unwrap();
+            assert_eq!(
+                bounding_box,
+                BoundingBox {
+                    x: 0.0,
+                    y: 0.0,
+                    width: 100.0,
+                    height: 100.0,
+                }
+            );
+
+            let text = left.to_string();
+            assert!(text.starts_with("<g>"));
+            assert!(!text.contains("viewBox"));
+            assert!(text.contains(r#"<clipPath id="left-p_0">"#));
+
+            let options = PieChartOptions {
+                id_prefix: Some("right-".to_string()),
+                ..Default::default()
+            };
+            let (right, _) = create_pie_chart_group(100, 100, &case, &options).unwrap();
+            let document = Document::new()
+                .set("viewBox", (0, 0, 200, 100))
+                .add(left)
+                .add(right.set("transform", "translate(100, 0)"));
+
+            let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("test_target/test_create_pie_chart_group.svg");
+            svg::save(path, &document).unwrap();
+        }
     }
 }
@@ -0,0 +1,463 @@
+use svg::node::element::Group;
+use svg::{Document, Node};
+
+use crate::category::{assign_colors, find_color};
+use crate::error::PieChartError;
+use crate::layout::{self, Area};
+use crate::legend::layout_legend;
+use crate::options::{
+    CategoryColors, ChartText, LegendOptions, PieChartOptions, ProportionalPiesOptions,
+    SmallMultiplesOptions,
+};
+use crate::size::apply_size;
+
+/// 複数の円グラフを並べる文書。
+struct Canvas {
+    document: Document,
+    /// カテゴリーと色の組。
+    colors: Vec<(String, String)>,
+    is_rtl: bool,
+    /// 凡例を除いた、円グラフを並べる範囲。
+    area: Area,
+}
+
+/// 複数のデータセットの円グラフを、格子状に並べる。
+pub(crate) fn create_small_multiples<N, D, T>(
+    width: u32,
+    height: u32,
+    datasets: &[(N, D)],
+    options: &SmallMultiplesOptions,
+) -> Result<Document, PieChartError>
+where
+    N: AsRef<str>,
+    D: AsRef<[(T, f64)]>,
+    T: AsRef<str>,
+{
+    let Canvas {
+        mut document,
+        colors,
+        is_rtl,
+        area,
+    } = create_canvas(
+        width,
+        height,
+        datasets.iter().map(|(_, dataset)| dataset.as_ref()),
+        &options.colors,
+        &options.chart,
+        options.legend.as_ref(),
+    );
+
+    let (cell_size, origins) =
+        layout_cells(&area, datasets.len(), options.columns, options.gap, is_rtl);
+    for (i, ((name, dataset), origin)) in datasets.iter().zip(origins).enumerate() {
+        let statuses = create_statuses(dataset.as_ref(), &colors);
+        let cell_options = create_cell_options(
+            &options.chart,
+            options.cell_title.as_ref(),
+            name.as_ref(),
+            i,
+        );
+        document.append(create_cell(cell_size, origin, &statuses, &cell_options)?);
+    }
+
+    Ok(document)
+}
+
+/// 合計に比例する面積の円グラフを、横に並べる。
+pub(crate) fn create_proportional_pies<N, D, T>(
+    width: u32,
+    height: u32,
+    datasets: &[(N, f64, D)],
+    options: &ProportionalPiesOptions,
+) -> Result<Document, PieChartError>
+where
+    N: AsRef<str>,
+    D: AsRef<[(T, f64)]>,
+    T: AsRef<str>,
+{
+    let Canvas {
+        mut document,
+        colors,
+        is_rtl,
+        area,
+    } = create_canvas(
+        width,
+        height,
+        datasets.iter().map(|(_, _, dataset)| dataset.as_ref()),
+        &options.colors,
+        &options.chart,
+        options.legend.as_ref(),
+    );
+
+    let columns = datasets.len().max(1) as u32;
+    let (cell_size, origins) = layout_cells(&area, datasets.len(), columns, options.gap, is_rtl);
+
+    let cells: Vec<_> = datasets
+        .iter()
+        .enumerate()
+        .map(|(i, (name, total, dataset))| {
+            let mut cell_options = create_cell_options(
+                &options.chart,
+                options.cell_title.as_ref(),
+                name.as_ref(),
+                i,
+            );
+            cell_options.total = Some(*total);
+            cell_options.source = options
+                .total_annotation
+                .as_ref()
+                .map(|annotation| ChartText {
+                    text: annotation.format.format(*total),
+                    ..annotation.style.clone()
+                });
+            (create_statuses(dataset.as_ref(), &colors), cell_options)
+        })
+        .collect();
+
+    // 基準の合計の円を、全てのセルに収まる最大の半径にする
+    let max_radius = cells
+        .iter()
+        .map(|(statuses, cell_options)| {
+            crate::fit_chart_radius(
+                cell_size.0 as u32,
+                cell_size.1 as u32,
+                statuses,
+                cell_options,
+            )
+        })
+        .min()
+        .unwrap_or(0);
+    let reference_total = options.reference_total.unwrap_or_else(|| {
+        datasets
+            .iter()
+            .map(|(_, total, _)| *total)
+            .fold(0.0, f64::max)
+    });
+
+    for (((_, total, _), (statuses, mut cell_options)), origin) in
+        datasets.iter().zip(cells).zip(origins)
+    {
+        let radius = calc_proportional_radius(max_radius, *total, reference_total);
+        cell_options.circle_radius = Some(radius);
+        // 円が無い場合は、ラベルが中心に重なるため、見出しと注記だけの枠にする
+        let statuses = if radius == 0 { Vec::new() } else { statuses };
+        document.append(create_cell(cell_size, origin, &statuses, &cell_options)?);
+    }
+
+    Ok(document)
+}
+
+/// 面積が`total`に比例するよう、半径を計算する。
+///
+/// `reference_total`の円の半径が`max_radius`になる。
+fn calc_proportional_radius(max_radius: u32, total: f64, reference_total: f64) -> u32 {
+    if reference_total <= 0.0 || total <= 0.0 {
+        return 0;
+    }
+
+    (max_radius as f64 * (total / reference_total).sqrt()).round() as u32
+}
+
+/// 文書を作成し、全てのデータセットのカテゴリーに色を割り当て、共有する凡例を置く。
+fn create_canvas<'a, T>(
+    width: u32,
+    height: u32,
+    datasets: impl Iterator<Item = &'a [(T, f64)]>,
+    category_colors: &CategoryColors,
+    chart: &PieChartOptions,
+    legend: Option<&LegendOptions>,
+) -> Canvas
+where
+    T: AsRef<str> + 'a,
+{
+    let colors = assign_colors(
+        datasets.flatten().map(|(category, _)| category.as_ref()),
+        category_colors,
+    );
+    let category_names: Vec<&str> = colors.iter().map(|(name, _)| name.as_str()).collect();
+    let is_rtl = chart.direction.is_rtl(&category_names.join(" "));
+
+    let mut document = Document::new().set("viewBox", format!("0, 0, {width}, {height}"));
+    document = apply_size(document, width, height, &chart.size);
+    if is_rtl {
+        document.assign("direction", "rtl");
+    }
+
+    let mut area = Area::from_size(width, height);
+
+    // 凡例は全ての円で1つにする
+    if let Some(legend_options) = legend {
+        let legend_options = LegendOptions {
+            value: None,
+            ..legend_options.clone()
+        };
+        let statuses: Vec<(&str, f64, &str)> = colors
+            .iter()
+            .map(|(name, color)| (name.as_str(), 0.0, color.as_str()))
+            .collect();
+        let legend = layout_legend(
+            &statuses,
+            &legend_options,
+            None,
+            (
+                area.width() - layout::SPACING * 2.0,
+                area.height() - layout::SPACING * 2.0,
+            ),
+        );
+        let origin = layout::place_legend(&mut area, legend.position(is_rtl), legend.size());
+        document.append(legend.create(origin, is_rtl));
+    }
+
+    Canvas {
+        document,
+        colors,
+        is_rtl,
+        area,
+    }
+}
+
+/// `area`を格子に分け、セルの幅と高さ、各セルの左上の位置を計算する。
+///
+/// 右から左へ書くグラフでは、列の順番を反転する。
+fn layout_cells(
+    area: &Area,
+    count: usize,
+    columns: u32,
+    gap: u32,
+    is_rtl: bool,
+) -> ((f64, f64), Vec<(f64, f64)>) {
+    let columns = (columns.max(1) as usize).min(count.max(1));
+    let rows = count.max(1).div_ceil(columns);
+    let gap = gap as f64;
+    let cell_width = ((area.width() - gap * (columns - 1) as f64) / columns as f64)
+        .floor()
+        .max(0.0);
+    let cell_height = ((area.height() - gap * (rows - 1) as f64) / rows as f64)
+        .floor()
+        .max(0.0);
+
+    let origins = (0..count)
+        .map(|i| {
+            let column = if is_rtl {
+                columns - 1 - i % columns
+            } else {
+                i % columns
+            };
+            let row = i / columns;
+            (
+                area.left + (cell_width + gap) * column as f64,
+                area.top + (cell_height + gap) * row as f64,
+            )
+        })
+        .collect();
+
+    ((cell_width, cell_height), origins)
+}
+
+/// データセットの各カテゴリーに、割り当てた色を付ける。
+fn create_statuses<'a, T>(
+    dataset: &'a [(T, f64)],
+    colors: &'a [(String, String)],
+) -> Vec<(&'a str, f64, &'a str)>
+where
+    T: AsRef<str>,
+{
+    dataset
+        .iter()
+        .map(|(category, ratio)| {
+            let category = category.as_ref();
+            (category, *ratio, find_color(colors, category))
+        })
+        .collect()
+}
+
+/// `i`番目のセルのオプションを作成する。
+fn create_cell_options(
+    chart: &PieChartOptions,
+    cell_title: Option<&ChartText>,
+    name: &str,
+    i: usize,
+) -> PieChartOptions {
+    let mut cell_options = chart.clone();
+    cell_options.title = cell_title.map(|cell_title| ChartText {
+        text: name.to_string(),
+        ..cell_title.clone()
+    });
+    cell_options.legend = None;
+    // 指定された接頭辞は、セルごとに区別する
+    // 接頭辞の末尾と番号が続かないよう、区切る (`map`の10番目と`map1`の0番目など)
+    cell_options.id_prefix = chart
+        .id_prefix
+        .as_ref()
+        .map(|id_prefix| format!("{id_prefix}-{i}-"));
+
+    cell_options
+}
+
+/// セルの円グラフを作成し、`origin`へ移動する。
+fn create_cell(
+    (cell_width, cell_height): (f64, f64),
+    (x, y): (f64, f64),
+    statuses: &[(&str, f64, &str)],
+    cell_options: &PieChartOptions,
+) -> Result<Group, PieChartError> {
+    let mut group = Group::new();
+    crate::append_chart(
+        &mut group,
+        cell_width as u32,
+        cell_height as u32,
+        statuses,
+        cell_options,
+        false,
+    )?;
+
+    Ok(group.set("transform", format!("translate({x}, {y})")))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::options::{NumberFormat, TotalAnnotation};
+
+    mod create_small_multiples {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_data() {
+            let datasets = [
+                ("North", vec![("Red", 0.5), ("Green", 0.5)]),
+                ("South", vec![("Red", 0.25), ("Blue", 0.75)]),
+                ("East", vec![("Green", 0.6), ("Blue", 0.4)]),
+                ("West", vec![("Blue", 0.3), ("Red", 0.7)]),
+            ];
+            let options = SmallMultiplesOptions {
+                columns: 2,
+                ..Default::default()
+            };
+
+            let document = create_small_multiples(300, 200, &datasets, &options).unwrap();
+            let text = document.to_string();
+            // 凡例は1つだけ
+            assert_eq!(text.matches("<rect").count(), 3);
+            // 同じカテゴリーは同じ色
+            assert_eq!(text.matches(r##"fill="#4e79a7" r="##).count(), 3);
+            assert_eq!(text.matches(r##"fill="#59a14f""##).count(), 0);
+            // 凡例の幅 (見本10、間隔5、"Green"30) と余白を除いて並べる
+            assert!(text.contains(r#"<g transform="translate(0, 0)">"#));
+            assert!(text.contains(r#"<g transform="translate(127, 104)">"#));
+            assert!(text.contains("West"));
+
+            let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("test_target/test_create_small_multiples.svg");
+            svg::save(path, &document).unwrap();
+        }
+
+        #[test]
+        fn success_when_prefixes_in_one_page() {
+            let datasets: Vec<(String, Vec<(&str, f64)>)> = (0..11)
+                .map(|i| (format!("Cell {i}"), vec![("Red", 0.5), ("Blue", 0.5)]))
+                .collect();
+            let create_options = |id_prefix: &str| SmallMultiplesOptions {
+                chart: PieChartOptions {
+                    id_prefix: Some(id_prefix.to_string()),
+                    ..SmallMultiplesOptions::default().chart
+                },
+                legend: None,
+                ..Default::default()
+            };
+
+            // `map`の10番目と`map1`の0番目のidが重ならない
+            let html = format!(
+                "{}\n{}",
+                create_small_multiples(300, 300, &datasets, &create_options("map")).unwrap(),
+                create_small_multiples(300, 300, &datasets[..1], &create_options("map1")).unwrap()
+            );
+            let ids: Vec<&str> = html
+                .split(" id=\"")
+                .skip(1)
+                .map(|rest| rest.split('"').next().unwrap())
+                .collect();
+            let mut unique_ids = ids.clone();
+            unique_ids.sort();
+            unique_ids.dedup();
+            assert_eq!(ids.len(), unique_ids.len());
+            assert!(ids.contains(&"map-10-p_0"));
+            assert!(ids.contains(&"map1-0-p_0"));
+        }
+    }
+
+    mod create_proportional_pies {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_data() {
+            let datasets = [
+                ("2024", 1000.0, vec![("Red", 0.5), ("Blue", 0.5)]),
+                ("2025", 4000.0, vec![("Red", 0.25), ("Blue", 0.75)]),
+            ];
+            let options = ProportionalPiesOptions {
+                total_annotation: Some(TotalAnnotation {
+                    format: NumberFormat {
+                        prefix: "$".to_string(),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                }),
+                legend: None,
+                ..Default::default()
+            };
+
+            let document = create_proportional_pies(200, 100, &datasets, &options).unwrap();
+            let text = document.to_string();
+            // 見出しと注記を除いた高さ60に収まる半径30が最大で、
+            // 合計が1/4の円は半径が1/2になる
+            assert!(text.contains(r##"fill="#4e79a7" r="15""##));
+            assert!(text.contains(r##"fill="#4e79a7" r="30""##));
+            assert!(text.contains("$4,000"));
+
+            let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("test_target/test_create_proportional_pies.svg");
+            svg::save(path, &document).unwrap();
+        }
+
+        #[test]
+        fn success_when_zero_total() {
+            let datasets = [
+                ("2024", 1000.0, vec![("Red", 0.5), ("Blue", 0.5)]),
+                ("2025", 0.0, vec![("Red", 0.0), ("Blue", 0.0)]),
+            ];
+            let options = ProportionalPiesOptions {
+                total_annotation: Some(TotalAnnotation::default()),
+                legend: None,
+                ..Default::default()
+            };
+
+            let document = create_proportional_pies(200, 100, &datasets, &options).unwrap();
+            let text = document.to_string();
+            // 合計が0の円は、見出しと注記だけを描く
+            let empty_cell = text
+                .split(r#"<g transform="translate(104, 0)">"#)
+                .nth(1)
+                .unwrap();
+            assert!(empty_cell.contains("<title>2025</title>"));
+            assert!(empty_cell.contains("\n0\n"));
+            assert!(!empty_cell.contains("<circle"));
+            assert!(!empty_cell.contains("Red"));
+            assert_eq!(text.matches("<circle").count(), 2);
+        }
+    }
+
+    mod calc_proportional_radius {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            assert_eq!(calc_proportional_radius(40, 100.0, 100.0), 40);
+            assert_eq!(calc_proportional_radius(40, 25.0, 100.0), 20);
+            assert_eq!(calc_proportional_radius(40, 0.0, 100.0), 0);
+            assert_eq!(calc_proportional_radius(40, 100.0, 0.0), 0);
+        }
+    }
+}
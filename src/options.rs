@@ -0,0 +1,1012 @@
+//! Options for creating pie chart.
+
+/// Options of [`create_pie_chart_with_options`](crate::create_pie_chart_with_options).
+///
+/// The `circle_radius`, `inner_radius`, `margin` and `padding` are in pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PieChartOptions {
+    /// Radius of the circle.
+    ///
+    /// `None` computes the largest radius with which the pie and its labels
+    /// fit in the chart besides titles and legend.
+    /// Otherwise the pie shrinks only when it does not fit.
+    pub circle_radius: Option<u32>,
+    /// Radius of the hole of donut chart.
+    ///
+    /// `0` draws a pie chart without a hole.
+    pub inner_radius: u32,
+    /// Total of the values which the ratios of pies are of.
+    ///
+    /// This is used to show values, e.g. [`CenterValue::Total`].
+    pub total: Option<f64>,
+    /// Content in the center of the chart, e.g. the total in the hole of donut chart.
+    ///
+    /// `None` shows nothing.
+    pub center: Option<CenterContent>,
+    /// Title above the chart.
+    ///
+    /// The title is also set as `<title>` of the document.
+    /// The pie is placed between the titles and the source.
+    pub title: Option<ChartText>,
+    /// Subtitle below the title.
+    pub subtitle: Option<ChartText>,
+    /// Footnote below the chart, e.g. `Source: internal survey 2026`.
+    pub source: Option<ChartText>,
+    /// Size of the root `<svg>` in the page.
+    pub size: ChartSize,
+    /// Space outside the background and the frame.
+    pub margin: Insets,
+    /// Space between the frame and the contents of the chart.
+    pub padding: Insets,
+    /// Background color inside the margin. RGB ((0 ~ 255) * 3).
+    ///
+    /// `None` leaves the background transparent.
+    pub background: Option<(u8, u8, u8)>,
+    /// Border frame inside the margin.
+    ///
+    /// `None` draws no frame.
+    pub frame: Option<FrameOptions>,
+    /// Options of labels.
+    pub label: LabelOptions,
+    /// Legend of the pies.
+    ///
+    /// The pie shrinks and shifts to make room for the legend.
+    /// `None` draws no legend.
+    pub legend: Option<LegendOptions>,
+    /// Prefix of the ids of generated elements, e.g. clip paths and masks,
+    /// and of the family names of embedded fonts.
+    ///
+    /// `None` uses a prefix made from a hash of the chart's contents,
+    /// so that different charts inlined in the same page do not collide.
+    /// Identical charts get the same ids, whose duplicated definitions are identical
+    /// and draw the same, but are invalid in a single HTML document.
+    /// Set distinct prefixes to inline identical charts in the same page.
+    ///
+    /// The prefix may contain only letters, digits, `-`, `_` and `.`,
+    /// and must not start with a digit, `-` or `.`.
+    /// Otherwise [`PieChartError::InvalidIdPrefix`](crate::error::PieChartError::InvalidIdPrefix)
+    /// is returned.
+    pub id_prefix: Option<String>,
+    /// Direction of the chart.
+    ///
+    /// Right-to-left charts set `direction="rtl"` on the root `<svg>`.
+    /// [`TextDirection::Auto`] follows the first label with a strong directional character.
+    pub direction: TextDirection,
+    /// Embeds the fonts of texts into `<style>` as `@font-face`,
+    /// so that the chart looks the same without the fonts installed.
+    ///
+    /// Only the glyphs of the used characters are embedded (subsetting).
+    /// Texts without `typography.font` and labels converted into outlines are not affected.
+    /// Only TrueType (`glyf`) fonts are supported, otherwise
+    /// [`PieChartError::FontNotSubsettable`](crate::error::PieChartError::FontNotSubsettable)
+    /// is returned.
+    #[cfg(feature = "font")]
+    pub embed_fonts: bool,
+}
+
+impl Default for PieChartOptions {
+    fn default() -> Self {
+        Self {
+            circle_radius: Some(40),
+            inner_radius: 0,
+            total: None,
+            center: None,
+            title: None,
+            subtitle: None,
+            source: None,
+            size: ChartSize::default(),
+            margin: Insets::default(),
+            padding: Insets::default(),
+            background: None,
+            frame: None,
+            label: LabelOptions::default(),
+            legend: None,
+            id_prefix: None,
+            direction: TextDirection::Auto,
+            #[cfg(feature = "font")]
+            embed_fonts: false,
+        }
+    }
+}
+
+/// Options of labels drawn on each pie.
+///
+/// The `position_radius` is in pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LabelOptions {
+    /// Color of label.
+    pub color: LabelColor,
+    /// Typography of label.
+    pub typography: Typography,
+    /// Size of label relative to the smaller of the chart's width and height, e.g. `0.05`.
+    ///
+    /// This overrides `typography.size`. `None` uses `typography.size`.
+    pub relative_size: Option<f64>,
+    /// Radius of label's position.
+    ///
+    /// `None` places labels at `position_ratio` of the circle's radius.
+    pub position_radius: Option<u32>,
+    /// Radius of label's position relative to the circle's radius.
+    ///
+    /// Values over `1.0` place labels outside the pie.
+    /// This is used when `position_radius` is `None`.
+    pub position_ratio: f64,
+    /// Labels of pies smaller than this threshold are hidden.
+    ///
+    /// `None` shows all labels.
+    pub hide_below: Option<LabelThreshold>,
+    /// Orientation of label.
+    pub orientation: LabelOrientation,
+    /// Halo (outline) around label.
+    ///
+    /// `None` draws no halo.
+    pub halo: Option<LabelHalo>,
+    /// Maximum width of label in pixels, or height for [`LabelOrientation::Vertical`].
+    ///
+    /// `None` does not limit the width.
+    /// The full text is kept in `<title>` when the label is ellipsized or wrapped.
+    pub max_width: Option<u32>,
+    /// How to fit label into `max_width`.
+    ///
+    /// [`LabelOrientation::Curved`] labels are always ellipsized.
+    pub overflow: LabelOverflow,
+    /// Ruby (furigana) written in labels as `｜東京《とうきょう》`.
+    ///
+    /// `None` shows the markup as it is.
+    pub ruby: Option<RubyOptions>,
+    /// Direction of label's text.
+    ///
+    /// `direction` and `unicode-bidi="embed"` are set on right-to-left labels,
+    /// and on all labels of right-to-left charts.
+    /// [`TextDirection::Auto`] detects it from the first strong directional character of each label.
+    ///
+    /// Outlines of labels are ordered by a simplified Unicode bidirectional algorithm,
+    /// which keeps the order of numbers and left-to-right words in right-to-left labels,
+    /// but scripts which need shaping (e.g. Arabic) are not supported.
+    pub direction: TextDirection,
+    /// Converts label into `<path>` outlines with `typography.font`,
+    /// so that it looks identical in any viewer.
+    ///
+    /// The original text is kept in `<title>`.
+    /// [`PieChartError::FontRequired`](crate::error::PieChartError::FontRequired)
+    /// is returned when `typography.font` is `None`, and
+    /// [`PieChartError::MissingGlyph`](crate::error::PieChartError::MissingGlyph)
+    /// when the font lacks a character of the label.
+    #[cfg(feature = "font")]
+    pub outline: bool,
+}
+
+impl Default for LabelOptions {
+    fn default() -> Self {
+        Self {
+            color: LabelColor::Fixed((0, 0, 0)),
+            typography: Typography::default(),
+            relative_size: None,
+            position_radius: Some(20),
+            position_ratio: 0.5,
+            hide_below: None,
+            orientation: LabelOrientation::Horizontal,
+            halo: Some(LabelHalo::default()),
+            max_width: None,
+            overflow: LabelOverflow::default(),
+            ruby: None,
+            direction: TextDirection::Auto,
+            #[cfg(feature = "font")]
+            outline: false,
+        }
+    }
+}
+
+/// Typography of texts.
+///
+/// The `size` and `letter_spacing` are in pixels.
+///
+/// Widths of texts are needed for layout, e.g. ellipsizing.
+/// With the `font` feature and `font`, they are measured with real glyph advances of the font.
+/// Otherwise, they are estimated: full-width (East Asian Wide) characters are `size` wide,
+/// and the others are `size * 0.6` wide.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Typography {
+    /// Font stack as a value of CSS `font-family`, e.g. `"Noto Sans JP", sans-serif`.
+    ///
+    /// Each family is quoted only when it is needed, and generic families are never quoted.
+    /// An empty string omits `font-family`.
+    pub font_family: String,
+    /// Size of text.
+    pub size: u32,
+    /// CSS `font-weight`, e.g. `bold` or `600`.
+    pub weight: Option<String>,
+    /// CSS `font-style`, e.g. `italic`.
+    pub style: Option<String>,
+    /// CSS `letter-spacing`.
+    pub letter_spacing: Option<f64>,
+    /// Height of a line as a multiple of `size`, for texts of multiple lines.
+    pub line_height: f64,
+    /// SVG `dominant-baseline`, e.g. `central` to center text vertically on its position.
+    pub dominant_baseline: Option<String>,
+    /// Local font for measuring texts.
+    ///
+    /// This does not change `font-family`, so that the font should be one of `font_family`.
+    #[cfg(feature = "font")]
+    pub font: Option<crate::font::Font>,
+}
+
+impl Default for Typography {
+    fn default() -> Self {
+        Self {
+            font_family: "sans-serif".to_string(),
+            size: 10,
+            weight: None,
+            style: None,
+            letter_spacing: None,
+            line_height: 1.2,
+            dominant_baseline: None,
+            #[cfg(feature = "font")]
+            font: None,
+        }
+    }
+}
+
+/// Color of labels.
+///
+/// The halo around labels is black or white, whichever has the higher contrast with the label.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LabelColor {
+    /// Same color for all labels. RGB ((0 ~ 255) * 3).
+    Fixed((u8, u8, u8)),
+    /// Black or white for each label, whichever has the higher contrast
+    /// (WCAG relative luminance) with the color of the pie.
+    ///
+    /// Black is used when the color of the pie is not parsable.
+    Auto,
+}
+
+impl From<(u8, u8, u8)> for LabelColor {
+    fn from(value: (u8, u8, u8)) -> Self {
+        LabelColor::Fixed(value)
+    }
+}
+
+/// Halo (outline) around labels.
+///
+/// The `width` is in pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LabelHalo {
+    /// Color of halo. RGB ((0 ~ 255) * 3).
+    ///
+    /// `None` uses black or white, whichever has the higher contrast with the label.
+    pub color: Option<(u8, u8, u8)>,
+    /// Width of halo's stroke.
+    pub width: f64,
+    /// Opacity of halo (0.0 ~ 1.0).
+    pub opacity: f64,
+    /// Draws halo with `paint-order="stroke"` on a single `<text>`,
+    /// instead of a duplicated `<text>` under the label.
+    pub paint_order: bool,
+}
+
+impl Default for LabelHalo {
+    fn default() -> Self {
+        Self {
+            color: None,
+            width: 2.0,
+            opacity: 1.0,
+            paint_order: false,
+        }
+    }
+}
+
+/// How to fit labels into [`LabelOptions::max_width`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LabelOverflow {
+    /// Truncates label with an ellipsis (`…`).
+    #[default]
+    Ellipsis,
+    /// Wraps label into lines.
+    ///
+    /// Lines are broken after spaces and around full-width characters,
+    /// following the Japanese line breaking rules (kinsoku), e.g. no break before `。`, `、`, and `）`.
+    /// The last line is ellipsized when the lines exceed `max_lines`.
+    Wrap {
+        /// Maximum number of lines. `None` does not limit the number.
+        ///
+        /// `Some(0)` is treated as `Some(1)`, so that the label is not hidden.
+        max_lines: Option<u32>,
+    },
+}
+
+/// Content in the center of the chart.
+///
+/// The primary line is placed above the secondary line, and both are centered on the chart.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CenterContent {
+    /// Primary line, e.g. a key metric.
+    pub primary: CenterLine,
+    /// Secondary line, e.g. a unit or a caption.
+    ///
+    /// `None` shows only the primary line.
+    pub secondary: Option<CenterLine>,
+    /// Scales the lines down to fit in the hole of donut chart.
+    ///
+    /// Nothing is scaled when [`PieChartOptions::inner_radius`] is `0`.
+    pub auto_scale: bool,
+}
+
+impl Default for CenterContent {
+    fn default() -> Self {
+        Self {
+            primary: CenterLine::default(),
+            secondary: None,
+            auto_scale: true,
+        }
+    }
+}
+
+/// A line of [`CenterContent`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CenterLine {
+    /// Value of the line.
+    pub value: CenterValue,
+    /// Typography of the line.
+    pub typography: Typography,
+    /// Color of the line. RGB ((0 ~ 255) * 3).
+    pub color: (u8, u8, u8),
+}
+
+impl Default for CenterLine {
+    fn default() -> Self {
+        Self {
+            value: CenterValue::Total(NumberFormat::default()),
+            typography: Typography {
+                size: 16,
+                ..Default::default()
+            },
+            color: (0, 0, 0),
+        }
+    }
+}
+
+/// Value of [`CenterLine`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum CenterValue {
+    /// Fixed text.
+    Text(String),
+    /// Computed total of the values, i.e. [`PieChartOptions::total`].
+    ///
+    /// When `total` is `None`, the sum of the ratios is shown in percent,
+    /// without `prefix` and `suffix` of the format.
+    Total(NumberFormat),
+}
+
+/// Format of numbers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumberFormat {
+    /// Number of digits after the decimal point.
+    pub decimals: usize,
+    /// Separator of thousands, e.g. `,` for `1,234`.
+    ///
+    /// `None` does not separate thousands.
+    pub thousands_separator: Option<String>,
+    /// Text before the number, e.g. `$`.
+    pub prefix: String,
+    /// Text after the number, e.g. ` users`.
+    pub suffix: String,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self {
+            decimals: 0,
+            thousands_separator: Some(",".to_string()),
+            prefix: String::new(),
+            suffix: String::new(),
+        }
+    }
+}
+
+impl NumberFormat {
+    /// Formats `value` in this format.
+    pub(crate) fn format(&self, value: f64) -> String {
+        let formatted = format!("{:.*}", self.decimals, value.abs());
+        let (integer, fraction) = match formatted.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (formatted.as_str(), None),
+        };
+
+        let integer = match &self.thousands_separator {
+            Some(separator) => {
+                // 下の桁から3桁ずつ区切る
+                let digits: Vec<char> = integer.chars().rev().collect();
+                let groups: Vec<String> = digits
+                    .chunks(3)
+                    .rev()
+                    .map(|chunk| chunk.iter().rev().collect())
+                    .collect();
+                groups.join(separator)
+            }
+            None => integer.to_string(),
+        };
+
+        // 丸めて0になる場合は、符号を付けない
+        let sign = if value < 0.0 && formatted.chars().any(|c| c.is_ascii_digit() && c != '0') {
+            "-"
+        } else {
+            ""
+        };
+        let number = match fraction {
+            Some(fraction) => format!("{integer}.{fraction}"),
+            None => integer,
+        };
+
+        format!("{sign}{}{number}{}", self.prefix, self.suffix)
+    }
+
+    /// 百分率の書式にする。
+    ///
+    /// 値の単位は百分率と異なるため、接頭辞と接尾辞は使わない。
+    pub(crate) fn to_percentage(&self) -> Self {
+        Self {
+            prefix: String::new(),
+            suffix: "%".to_string(),
+            ..self.clone()
+        }
+    }
+}
+
+/// Text around the chart, e.g. title.
+///
+/// Texts wider than the chart are wrapped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartText {
+    /// Text.
+    pub text: String,
+    /// Typography of the text.
+    pub typography: Typography,
+    /// Color of the text. RGB ((0 ~ 255) * 3).
+    pub color: (u8, u8, u8),
+    /// Horizontal alignment of the text.
+    pub align: TextAlign,
+}
+
+impl Default for ChartText {
+    fn default() -> Self {
+        Self {
+            text: String::new(),
+            typography: Typography::default(),
+            color: (0, 0, 0),
+            align: TextAlign::Center,
+        }
+    }
+}
+
+/// Horizontal alignment of texts.
+///
+/// `Start` and `End` are mirrored in right-to-left charts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextAlign {
+    /// Left in left-to-right charts.
+    Start,
+    /// Center.
+    #[default]
+    Center,
+    /// Right in left-to-right charts.
+    End,
+}
+
+/// Size of the chart in the page.
+///
+/// The `viewBox` is always the `width` and `height` in pixels given to the chart,
+/// so that only the displayed size differs.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum ChartSize {
+    /// Sets `width` and `height` in pixels.
+    #[default]
+    Pixels,
+    /// Fills the container with `width="100%"` and `height="100%"`.
+    Fluid {
+        /// Value of `preserveAspectRatio`, e.g. `xMidYMid meet`.
+        preserve_aspect_ratio: String,
+    },
+    /// Sets `width` and `height` in physical units for print,
+    /// converting pixels at `dpi` dots per inch.
+    Physical {
+        /// Unit of the size.
+        unit: PhysicalUnit,
+        /// Dots per inch.
+        dpi: f64,
+    },
+}
+
+/// Physical units of length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PhysicalUnit {
+    /// Millimeters (`mm`).
+    #[default]
+    Millimeter,
+    /// Inches (`in`).
+    Inch,
+}
+
+/// Space on each side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Insets {
+    /// Top.
+    pub top: u32,
+    /// Right.
+    pub right: u32,
+    /// Bottom.
+    pub bottom: u32,
+    /// Left.
+    pub left: u32,
+}
+
+impl Insets {
+    /// Creates the same space on all sides.
+    pub fn uniform(value: u32) -> Self {
+        Self {
+            top: value,
+            right: value,
+            bottom: value,
+            left: value,
+        }
+    }
+}
+
+/// Border frame around the chart.
+///
+/// The `width` and `corner_radius` are in pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameOptions {
+    /// Color of the border. RGB ((0 ~ 255) * 3).
+    pub color: (u8, u8, u8),
+    /// Width of the border.
+    pub width: f64,
+    /// Radius of the rounded corners.
+    ///
+    /// The background is also rounded.
+    pub corner_radius: f64,
+}
+
+impl Default for FrameOptions {
+    fn default() -> Self {
+        Self {
+            color: (0, 0, 0),
+            width: 1.0,
+            corner_radius: 0.0,
+        }
+    }
+}
+
+/// Options of legend.
+///
+/// Each item of legend has a color swatch and the label of the pie.
+/// The `swatch_size` is in pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LegendOptions {
+    /// Position of legend.
+    pub position: LegendPosition,
+    /// Typography of the items.
+    pub typography: Typography,
+    /// Color of the texts. RGB ((0 ~ 255) * 3).
+    pub color: (u8, u8, u8),
+    /// Width and height of the color swatches.
+    pub swatch_size: u32,
+    /// Value shown after the label.
+    ///
+    /// `None` shows only the label.
+    pub value: Option<LegendValue>,
+    /// Number of columns.
+    ///
+    /// `None` wraps the items into as many columns as needed (left and right)
+    /// or as fit in the width (top and bottom).
+    pub columns: Option<u32>,
+}
+
+impl Default for LegendOptions {
+    fn default() -> Self {
+        Self {
+            position: LegendPosition::Right,
+            typography: Typography::default(),
+            color: (0, 0, 0),
+            swatch_size: 10,
+            value: None,
+            columns: None,
+        }
+    }
+}
+
+/// Position of legend.
+///
+/// `Left` and `Right` are mirrored in right-to-left charts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LegendPosition {
+    /// Above the pie.
+    Top,
+    /// Right of the pie.
+    #[default]
+    Right,
+    /// Below the pie.
+    Bottom,
+    /// Left of the pie.
+    Left,
+}
+
+/// Value shown in legend.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LegendValue {
+    /// Value of the pie, i.e. the ratio multiplied by [`PieChartOptions::total`].
+    ///
+    /// When `total` is `None`, the ratio is shown in percent,
+    /// without `prefix` and `suffix` of the format.
+    Value(NumberFormat),
+    /// Ratio of the pie in percent.
+    Percentage(NumberFormat),
+}
+
+/// Colors of categories shared among several pies.
+///
+/// Categories without `colors` get the colors of `palette` in order of first appearance.
+/// The palette is repeated when there are more categories than its colors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CategoryColors {
+    /// Colors of categories (CSS style), e.g. `("Red party", "#fe5555")`.
+    pub colors: Vec<(String, String)>,
+    /// Colors (CSS style) for the other categories.
+    pub palette: Vec<String>,
+}
+
+impl Default for CategoryColors {
+    fn default() -> Self {
+        Self {
+            colors: Vec::new(),
+            palette: [
+                "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1",
+                "#ff9da7", "#9c755f", "#bab0ac",
+            ]
+            .into_iter()
+            .map(String::from)
+            .collect(),
+        }
+    }
+}
+
+/// Options of [`create_small_multiples`](crate::create_small_multiples).
+///
+/// The `gap` is in pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SmallMultiplesOptions {
+    /// Number of columns of the grid.
+    pub columns: u32,
+    /// Space between the cells.
+    pub gap: u32,
+    /// Options of the chart in each cell.
+    ///
+    /// `title` is replaced with `cell_title`, and `legend` is ignored.
+    /// `id_prefix`, if any, becomes `{id_prefix}-{index}-` in each cell.
+    pub chart: PieChartOptions,
+    /// Title of each cell, whose `text` is replaced with the name of the dataset.
+    ///
+    /// `None` shows no titles.
+    pub cell_title: Option<ChartText>,
+    /// Legend shared by all the cells.
+    ///
+    /// `value` is ignored. `None` draws no legend.
+    pub legend: Option<LegendOptions>,
+    /// Colors of categories.
+    pub colors: CategoryColors,
+}
+
+impl Default for SmallMultiplesOptions {
+    fn default() -> Self {
+        Self {
+            columns: 3,
+            gap: 8,
+            chart: PieChartOptions {
+                circle_radius: None,
+                label: LabelOptions {
+                    position_radius: None,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            cell_title: Some(ChartText::default()),
+            legend: Some(LegendOptions::default()),
+            colors: CategoryColors::default(),
+        }
+    }
+}
+
+/// Options of [`create_proportional_pies`](crate::create_proportional_pies).
+///
+/// The `gap` is in pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProportionalPiesOptions {
+    /// Space between the pies.
+    pub gap: u32,
+    /// Total of the pie which has the largest radius fitting in every cell.
+    ///
+    /// `None` uses the largest total of the datasets.
+    /// Pies of larger totals than this shrink to fit in their cells.
+    /// Pies whose radius becomes 0, e.g. of datasets whose totals are 0 or less,
+    /// are drawn as empty slots with only `cell_title` and `total_annotation`.
+    pub reference_total: Option<f64>,
+    /// Options of each pie chart.
+    ///
+    /// `circle_radius` and `total` are computed, `title` is replaced with `cell_title`,
+    /// `source` is replaced with `total_annotation`, and `legend` is ignored.
+    pub chart: PieChartOptions,
+    /// Title of each pie, whose `text` is replaced with the name of the dataset.
+    ///
+    /// `None` shows no titles.
+    pub cell_title: Option<ChartText>,
+    /// Total shown below each pie.
+    ///
+    /// `None` shows no totals.
+    pub total_annotation: Option<TotalAnnotation>,
+    /// Legend shared by all the pies.
+    ///
+    /// `value` is ignored. `None` draws no legend.
+    pub legend: Option<LegendOptions>,
+    /// Colors of categories.
+    pub colors: CategoryColors,
+}
+
+impl Default for ProportionalPiesOptions {
+    fn default() -> Self {
+        Self {
+            gap: 8,
+            reference_total: None,
+            chart: PieChartOptions {
+                label: LabelOptions {
+                    position_radius: None,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            cell_title: Some(ChartText::default()),
+            total_annotation: None,
+            legend: Some(LegendOptions::default()),
+            colors: CategoryColors::default(),
+        }
+    }
+}
+
+/// Total shown with a pie.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TotalAnnotation {
+    /// Format of the total.
+    pub format: NumberFormat,
+    /// Style of the text, whose `text` is replaced with the total.
+    pub style: ChartText,
+}
+
+/// Options of [`create_scatter_pies`](crate::create_scatter_pies).
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ScatterPiesOptions {
+    /// Minimum space in pixels between pies, which are moved apart not to overlap.
+    ///
+    /// `None` keeps the pies at the given coordinates.
+    pub avoid_overlap: Option<u32>,
+    /// Prefix of the ids of clip paths.
+    ///
+    /// Each pie uses `{id_prefix}-{index}-`.
+    /// `None` uses a prefix generated from the markers.
+    /// The same characters as [`PieChartOptions::id_prefix`] are allowed.
+    pub id_prefix: Option<String>,
+    /// Colors of categories.
+    pub colors: CategoryColors,
+}
+
+/// Options of [`create_spark_pie`](crate::create_spark_pie).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparkPieOptions {
+    /// Color of the ratio.
+    pub color: String,
+    /// Color of the ring behind the ratio.
+    ///
+    /// `None` draws no ring.
+    pub track: Option<String>,
+    /// Width of the ring, as a ratio to the radius (0.1 ~ 1.0).
+    ///
+    /// `1.0` draws a pie.
+    /// Values out of the range are clamped, so that the ring is always visible.
+    pub thickness: f64,
+}
+
+impl Default for SparkPieOptions {
+    fn default() -> Self {
+        Self {
+            color: "#4e79a7".to_string(),
+            track: Some("#e0e0e0".to_string()),
+            thickness: 1.0,
+        }
+    }
+}
+
+/// Ruby (furigana) annotations on labels.
+///
+/// Ruby is written as `｜東京《とうきょう》`, the style of Japanese novel markup.
+/// The `｜` (or `|`) can be omitted when the base text is a run of kanji, e.g. `東京《とうきょう》`.
+///
+/// Ruby is drawn above the base text, or on the right of it for [`LabelOrientation::Vertical`],
+/// and the height of each line is increased by the size of ruby.
+/// Ruby is not drawn on [`LabelOrientation::Curved`] labels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RubyOptions {
+    /// Size of ruby as a multiple of the label's size.
+    pub size_ratio: f64,
+}
+
+impl Default for RubyOptions {
+    fn default() -> Self {
+        Self { size_ratio: 0.5 }
+    }
+}
+
+/// Direction of texts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextDirection {
+    /// Detects the direction from the first strong directional character,
+    /// e.g. Hebrew or Arabic letters for right-to-left.
+    ///
+    /// Left-to-right is used when there is no strong directional character.
+    #[default]
+    Auto,
+    /// Left-to-right.
+    Ltr,
+    /// Right-to-left.
+    Rtl,
+}
+
+impl TextDirection {
+    /// Returns `true` when `text` is right-to-left in this direction.
+    pub(crate) fn is_rtl(&self, text: &str) -> bool {
+        match self {
+            TextDirection::Auto => text
+                .chars()
+                .find_map(|c| {
+                    if is_rtl_char(c) {
+                        Some(true)
+                    } else if c.is_alphabetic() {
+                        Some(false)
+                    } else {
+                        None
+                    }
+                })
+                .unwrap_or(false),
+            TextDirection::Ltr => false,
+            TextDirection::Rtl => true,
+        }
+    }
+}
+
+/// 右から左へ書く文字 (ヘブライ文字、アラビア文字など) かどうか。
+pub(crate) fn is_rtl_char(c: char) -> bool {
+    matches!(c as u32,
+        0x0590..=0x08FF
+        | 0xFB1D..=0xFDFF
+        | 0xFE70..=0xFEFF
+        | 0x10800..=0x10FFF
+        | 0x1E800..=0x1EFFF)
+}
+
+/// Threshold of a pie's size to show its label.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LabelThreshold {
+    /// Ratio of the pie (0.0 ~ 1.0).
+    Ratio(f64),
+    /// Angular width of the pie in radians.
+    Angle(f64),
+}
+
+impl LabelThreshold {
+    /// Returns `true` when a pie of `target_angle_range` is smaller than this threshold.
+    pub(crate) fn is_below(&self, target_angle_range: f64) -> bool {
+        let target_angle_range = target_angle_range.abs();
+        match self {
+            LabelThreshold::Ratio(ratio) => target_angle_range < std::f64::consts::TAU * ratio,
+            LabelThreshold::Angle(angle) => target_angle_range < *angle,
+        }
+    }
+}
+
+/// Orientation of labels.
+///
+/// Rotated labels are flipped automatically so that they are never upside down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LabelOrientation {
+    /// Labels are always horizontal.
+    #[default]
+    Horizontal,
+    /// Labels run along the radius.
+    Radial,
+    /// Labels run along the tangent to the arc.
+    Tangential,
+    /// Labels are laid along the arc of the pie at `position_radius`.
+    ///
+    /// Labels longer than the arc are ellipsized.
+    Curved,
+    /// Labels are written vertically (`writing-mode="vertical-rl"`),
+    /// centered on the bisector of the pie.
+    ///
+    /// All characters are set upright, both as text and as outlines,
+    /// and numbers of up to 2 digits are combined into a single column
+    /// (縦中横, `text-combine-upright`).
+    /// Wrapped lines are arranged from right to left.
+    Vertical,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod label_threshold {
+        use super::*;
+
+        mod is_below {
+            use std::f64::consts::{FRAC_PI_2, TAU};
+
+            use super::*;
+
+            #[test]
+            fn checking_value() {
+                assert!(LabelThreshold::Ratio(0.01).is_below(TAU * 0.005));
+                assert!(!LabelThreshold::Ratio(0.01).is_below(TAU * 0.01));
+                assert!(!LabelThreshold::Ratio(0.01).is_below(TAU * 0.5));
+
+                assert!(LabelThreshold::Angle(FRAC_PI_2).is_below(0.1));
+                assert!(!LabelThreshold::Angle(FRAC_PI_2).is_below(FRAC_PI_2));
+                assert!(!LabelThreshold::Angle(FRAC_PI_2).is_below(TAU));
+            }
+        }
+    }
+
+    mod text_direction {
+        use super::*;
+
+        mod is_rtl {
+            use super::*;
+
+            #[test]
+            fn checking_value() {
+                assert!(!TextDirection::Auto.is_rtl("Red"));
+                assert!(TextDirection::Auto.is_rtl("אדום"));
+                assert!(TextDirection::Auto.is_rtl("(123) أحمر"));
+                assert!(!TextDirection::Auto.is_rtl("東京 أحمر"));
+                assert!(!TextDirection::Auto.is_rtl("123"));
+                assert!(TextDirection::Rtl.is_rtl("Red"));
+                assert!(!TextDirection::Ltr.is_rtl("אדום"));
+            }
+        }
+    }
+
+    mod number_format {
+        use super::*;
+
+        mod format {
+            use super::*;
+
+            #[test]
+            fn checking_value() {
+                let format = NumberFormat::default();
+                assert_eq!(format.format(0.0), "0");
+                assert_eq!(format.format(123.0), "123");
+                assert_eq!(format.format(1234.0), "1,234");
+                assert_eq!(format.format(1234567.4), "1,234,567");
+                assert_eq!(format.format(-1234.0), "-1,234");
+                assert_eq!(format.format(-0.1), "0");
+
+                let format = NumberFormat {
+                    decimals: 2,
+                    thousands_separator: None,
+                    prefix: "$".to_string(),
+                    suffix: " users".to_string(),
+                };
+                assert_eq!(format.format(1234.5), "$1234.50 users");
+                // 符号は接頭辞の前
+                assert_eq!(format.format(-5.0), "-$5.00 users");
+                assert_eq!(format.to_percentage().format(50.0), "50.00%");
+            }
+        }
+    }
+}
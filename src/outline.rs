@@ -0,0 +1,418 @@
+use std::fmt::Write;
+
+use ttf_parser::OutlineBuilder;
+
+use crate::error::PieChartError;
+use crate::font::Font;
+use crate::options::Typography;
+use crate::text::{
+    calc_baseline_shift, measure_char_advances, measure_char_widths, measure_text_length,
+    split_upright_digits,
+};
+
+/// 文字列を並べる位置。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum TextPlacement {
+    /// `point`を中心に、時計回りに`rotation`度回転した直線上に並べる。
+    Straight { point: (f64, f64), rotation: f64 },
+    /// `from_angle`から`range`の範囲の円弧上に、中央揃えで並べる。
+    Arc {
+        center: (f64, f64),
+        radius: f64,
+        from_angle: f64,
+        range: f64,
+        clockwise: bool,
+    },
+    /// `point`を中心に、縦に並べる。文字は全て正立させ、1文字ごとに全角の高さで送る。
+    ///
+    /// 縦中横にする数字は横に並べ、全角の幅に収まるよう縮める。
+    Vertical { point: (f64, f64) },
+}
+
+/// 文字列の輪郭をパスのデータにする。
+///
+/// `dominant_baseline`は、`TextPlacement`の位置に合わせる基準線。
+/// フォントに無い文字がある場合は、エラーを返す。
+pub(crate) fn create_outline_data(
+    text: &str,
+    typography: &Typography,
+    font: &Font,
+    placement: TextPlacement,
+    dominant_baseline: &str,
+) -> Result<String, PieChartError> {
+    let face = font.face();
+    let scale = typography.size as f64 / face.units_per_em() as f64;
+    // 縦書きでは、文字を中央で揃える
+    let dominant_baseline = match placement {
+        TextPlacement::Vertical { .. } => "central",
+        _ => dominant_baseline,
+    };
+    let baseline_shift = calc_baseline_shift(
+        dominant_baseline,
+        face.ascender() as f64 * scale,
+        face.descender() as f64 * scale,
+    );
+
+    let widths = measure_char_widths(text, typography);
+    let total_width: f64 = widths.iter().sum();
+    let vertical_glyphs = match placement {
+        TextPlacement::Vertical { point } => layout_vertical(text, typography, point),
+        _ => Vec::new(),
+    };
+
+    let mut data = String::new();
+    let mut pen = 0.0;
+    for (i, (c, width)) in text.chars().zip(widths).enumerate() {
+        let (origin, direction, glyph_scale) = match placement {
+            TextPlacement::Straight { point, rotation } => {
+                let direction = rotation.to_radians().sin_cos();
+                let direction = (direction.1, direction.0);
+                let start = -total_width * 0.5 + pen;
+                (
+                    (point.0 + direction.0 * start, point.1 + direction.1 * start),
+                    direction,
+                    1.0,
+                )
+            }
+            TextPlacement::Arc {
+                center,
+                radius,
+                from_angle,
+                range,
+                clockwise,
+            } => {
+                let arc_length = radius * range;
+                let distance = (arc_length - total_width) * 0.5 + pen + width * 0.5;
+                let (angle, sign) = if clockwise {
+                    (from_angle - distance / radius, 1.0)
+                } else {
+                    (from_angle + distance / radius, -1.0)
+                };
+                let (sin, cos) = angle.sin_cos();
+                let direction = (sin * sign, cos * sign);
+                let point = (center.0 + radius * cos, center.1 - radius * sin);
+                (
+                    (
+                        point.0 - direction.0 * width * 0.5,
+                        point.1 - direction.1 * width * 0.5,
+                    ),
+                    direction,
+                    1.0,
+                )
+            }
+            TextPlacement::Vertical { .. } => {
+                let (origin, glyph_scale) = vertical_glyphs[i];
+                (origin, (1.0, 0.0), glyph_scale)
+            }
+        };
+        // 文字の上方向 (SVGの座標系)
+        let up = (direction.1, -direction.0);
+        let origin = (
+            origin.0 - up.0 * baseline_shift * glyph_scale,
+            origin.1 - up.1 * baseline_shift * glyph_scale,
+        );
+
+        match face.glyph_index(c) {
+            Some(glyph_id) => {
+                let mut builder = GlyphOutlineBuilder {
+                    data: &mut data,
+                    origin,
+                    direction,
+                    up,
+                    scale: scale * glyph_scale,
+                };
+                face.outline_glyph(glyph_id, &mut builder);
+            }
+            // 空白は輪郭が無いため、グリフが無くても描画は変わらない
+            None if c.is_whitespace() => {}
+            None => return Err(PieChartError::MissingGlyph(c)),
+        }
+
+        pen += width;
+    }
+
+    Ok(data)
+}
+
+/// 縦書きの各文字の、中央の高さでの左端の位置と、大きさの倍率を計算する。
+///
+/// 縦中横にする数字は横に並べ、全角の幅に収まるよう縮める。
+fn layout_vertical(
+    text: &str,
+    typography: &Typography,
+    (x, y): (f64, f64),
+) -> Vec<((f64, f64), f64)> {
+    let size = typography.size as f64;
+    let letter_spacing = typography.letter_spacing.unwrap_or(0.0);
+    let mut top = y - measure_text_length(text, typography, true) * 0.5;
+
+    let mut glyphs = Vec::new();
+    for (run, is_upright) in split_upright_digits(text) {
+        let widths = measure_char_widths(&run, typography);
+        if is_upright {
+            let advance = measure_text_length(&run, typography, true);
+            let run_width = widths.iter().sum::<f64>() - letter_spacing;
+            let glyph_scale = if run_width > size {
+                size / run_width
+            } else {
+                1.0
+            };
+            let mut left = x - run_width * glyph_scale * 0.5;
+            for width in widths {
+                glyphs.push(((left, top + advance * 0.5), glyph_scale));
+                left += width * glyph_scale;
+            }
+            top += advance;
+        } else {
+            let advances = measure_char_advances(&run, typography, true);
+            for (width, advance) in widths.into_iter().zip(advances) {
+                glyphs.push((
+                    (x - (width - letter_spacing) * 0.5, top + advance * 0.5),
+                    1.0,
+                ));
+                top += advance;
+            }
+        }
+    }
+
+    glyphs
+}
+
+struct GlyphOutlineBuilder<'a> {
+    data: &'a mut String,
+    origin: (f64, f64),
+    direction: (f64, f64),
+    up: (f64, f64),
+    scale: f64,
+}
+
+impl GlyphOutlineBuilder<'_> {
+    fn point(&self, x: f32, y: f32) -> String {
+        let x = x as f64 * self.scale;
+        let y = y as f64 * self.scale;
+        let point_x = self.origin.0 + self.direction.0 * x + self.up.0 * y;
+        let point_y = self.origin.1 + self.direction.1 * x + self.up.1 * y;
+
+        format!("{},{}", round(point_x), round(point_y))
+    }
+}
+
+impl OutlineBuilder for GlyphOutlineBuilder<'_> {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let point = self.point(x, y);
+        let _ = write!(self.data, "M{point}");
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let point = self.point(x, y);
+        let _ = write!(self.data, "L{point}");
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let control = self.point(x1, y1);
+        let point = self.point(x, y);
+        let _ = write!(self.data, "Q{control} {point}");
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let control1 = self.point(x1, y1);
+        let control2 = self.point(x2, y2);
+        let point = self.point(x, y);
+        let _ = write!(self.data, "C{control1} {control2} {point}");
+    }
+
+    fn close(&mut self) {
+        self.data.push('Z');
+    }
+}
+
+/// 小数点以下2桁に丸める。
+fn round(value: f64) -> f64 {
+    let value = (value * 100.0).round() / 100.0;
+    // -0を避ける
+    if value == 0.0 {
+        0.0
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::font::tests::create_test_font;
+
+    mod create_outline_data {
+        use super::*;
+
+        #[test]
+        fn checking_value_when_straight() {
+            let font = Font::from_bytes(create_test_font()).unwrap();
+            let typography = Typography {
+                size: 10,
+                ..Default::default()
+            };
+
+            // 幅6の"A"を(50, 50)を中心に配置する。
+            let data = create_outline_data(
+                "A",
+                &typography,
+                &font,
+                TextPlacement::Straight {
+                    point: (50.0, 50.0),
+                    rotation: 0.0,
+                },
+                "auto",
+            )
+            .unwrap();
+            assert_eq!(data, "M48,50L52,50L52,43L48,43L48,50Z");
+
+            let data = create_outline_data(
+                "A",
+                &typography,
+                &font,
+                TextPlacement::Straight {
+                    point: (50.0, 50.0),
+                    rotation: 90.0,
+                },
+                "auto",
+            )
+            .unwrap();
+            assert_eq!(data, "M50,48L50,52L57,52L57,48L50,48Z");
+
+            // 空白は輪郭を持たない
+            let data = create_outline_data(
+                " ",
+                &typography,
+                &font,
+                TextPlacement::Straight {
+                    point: (50.0, 50.0),
+                    rotation: 0.0,
+                },
+                "auto",
+            )
+            .unwrap();
+            assert_eq!(data, "");
+        }
+
+        #[test]
+        fn checking_value_when_central() {
+            let font = Font::from_bytes(create_test_font()).unwrap();
+            let typography = Typography {
+                size: 10,
+                ..Default::default()
+            };
+
+            // ascender 8、descender -2 なので基準線は3下がる
+            let data = create_outline_data(
+                "A",
+                &typography,
+                &font,
+                TextPlacement::Straight {
+                    point: (50.0, 50.0),
+                    rotation: 0.0,
+                },
+                "central",
+            )
+            .unwrap();
+            assert_eq!(data, "M48,53L52,53L52,46L48,46L48,53Z");
+        }
+
+        #[test]
+        fn checking_value_when_vertical() {
+            let font = Font::from_bytes(create_test_font()).unwrap();
+            let typography = Typography {
+                size: 10,
+                ..Default::default()
+            };
+
+            // 高さ10ずつ縦に並べ、それぞれを中央で揃える
+            let data = create_outline_data(
+                "AA",
+                &typography,
+                &font,
+                TextPlacement::Vertical {
+                    point: (50.0, 50.0),
+                },
+                "auto",
+            )
+            .unwrap();
+            assert_eq!(
+                data,
+                "M48,48L52,48L52,41L48,41L48,48ZM48,58L52,58L52,51L48,51L48,58Z"
+            );
+        }
+
+        #[test]
+        fn checking_value_when_arc() {
+            let font = Font::from_bytes(create_test_font()).unwrap();
+            let typography = Typography {
+                size: 10,
+                ..Default::default()
+            };
+
+            let data = create_outline_data(
+                "A",
+                &typography,
+                &font,
+                TextPlacement::Arc {
+                    center: (50.0, 50.0),
+                    radius: 20.0,
+                    from_angle: std::f64::consts::PI,
+                    range: std::f64::consts::PI,
+                    clockwise: true,
+                },
+                "auto",
+            )
+            .unwrap();
+            // 円の頂点に配置される
+            assert_eq!(data, "M48,30L52,30L52,23L48,23L48,30Z");
+        }
+
+        #[test]
+        fn failure_when_missing_glyph() {
+            let font = Font::from_bytes(create_test_font()).unwrap();
+            let typography = Typography {
+                size: 10,
+                ..Default::default()
+            };
+            let placement = TextPlacement::Straight {
+                point: (50.0, 50.0),
+                rotation: 0.0,
+            };
+
+            // テスト用のフォントに"D"は無い
+            let result = create_outline_data("ADB", &typography, &font, placement, "auto");
+            assert_eq!(result, Err(PieChartError::MissingGlyph('D')));
+
+            // 空白は輪郭が無いため、描画できる
+            assert!(create_outline_data("A B", &typography, &font, placement, "auto").is_ok());
+        }
+    }
+
+    mod layout_vertical {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            let typography = Typography::default();
+
+            // 高さ10ずつ送り、各文字を中央で揃える
+            assert_eq!(
+                layout_vertical("第1回", &typography, (50.0, 50.0)),
+                vec![
+                    ((45.0, 40.0), 1.0),
+                    ((47.0, 50.0), 1.0),
+                    ((45.0, 60.0), 1.0)
+                ]
+            );
+
+            // 縦中横の数字は横に並べ、全角の幅に縮める
+            let glyphs = layout_vertical("12", &typography, (50.0, 50.0));
+            assert_eq!(glyphs.len(), 2);
+            assert_eq!(glyphs[0].0, (45.0, 50.0));
+            assert!((glyphs[1].0 .0 - 50.0).abs() < 1e-9);
+            assert!((glyphs[1].1 - 10.0 / 12.0).abs() < 1e-9);
+        }
+    }
+}
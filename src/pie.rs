@@ -7,6 +7,7 @@ use crate::util::{
     calc_angle_coord, calc_point, normalize_angle, rotate_perpendicular_positive, Coord,
 };
 
+#[allow(clippy::too_many_arguments)]
 pub(super) fn create_pie(
     circle_center: Coord,
     circle_radius: u32,
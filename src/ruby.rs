@@ -0,0 +1,270 @@
+use crate::options::Typography;
+use crate::text::measure_text_length;
+
+/// ルビの範囲の開始を示す記号。
+const RUBY_START: [char; 2] = ['｜', '|'];
+/// ルビの開始を示す記号。
+const RUBY_OPEN: char = '《';
+/// ルビの終了を示す記号。
+const RUBY_CLOSE: char = '》';
+
+/// ルビ。`start`と`end`は、親文字の範囲 (文字単位)。
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Ruby {
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+}
+
+/// `｜東京《とうきょう》`の形式の記法を解析し、親文字だけの文字列とルビを返す。
+///
+/// `｜`が無い場合は、`《`の直前の漢字の並びを親文字とする。
+/// 閉じられていない記法は、そのままの文字列として扱う。
+pub(crate) fn parse_ruby(text: &str) -> (String, Vec<Ruby>) {
+    let chars: Vec<char> = text.chars().collect();
+    let mut base = Vec::new();
+    let mut rubies = Vec::new();
+    // `｜`による、親文字の範囲の開始位置
+    let mut explicit_start = None;
+
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if RUBY_START.contains(&c) && is_followed_by_ruby(&chars[i + 1..]) {
+            explicit_start = Some(base.len());
+            i += 1;
+            continue;
+        }
+
+        let close = (c == RUBY_OPEN)
+            .then(|| chars[i + 1..].iter().position(|c| *c == RUBY_CLOSE))
+            .flatten();
+        if let Some(close) = close {
+            let start = explicit_start.take().unwrap_or_else(|| {
+                base.len()
+                    - base
+                        .iter()
+                        .rev()
+                        .take_while(|c: &&char| is_kanji(**c))
+                        .count()
+            });
+            if start < base.len() {
+                rubies.push(Ruby {
+                    start,
+                    end: base.len(),
+                    text: chars[i + 1..i + 1 + close].iter().collect(),
+                });
+                i += close + 2;
+                continue;
+            }
+        }
+
+        base.push(c);
+        i += 1;
+    }
+
+    (base.into_iter().collect(), rubies)
+}
+
+/// `｜`の後の文字列`rest`が、親文字と閉じられた`《…》`で始まるかどうか。
+///
+/// 次の`｜`より前に`《`が無い場合や、親文字が空の場合は、ルビとしない。
+fn is_followed_by_ruby(rest: &[char]) -> bool {
+    let open = rest
+        .iter()
+        .position(|c| *c == RUBY_OPEN || RUBY_START.contains(c));
+    match open {
+        Some(open) if open > 0 && rest[open] == RUBY_OPEN => rest[open + 1..].contains(&RUBY_CLOSE),
+        _ => false,
+    }
+}
+
+/// ルビの親文字になる漢字かどうか。
+fn is_kanji(c: char) -> bool {
+    matches!(c as u32,
+        0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xF900..=0xFAFF
+        | 0x20000..=0x3FFFD)
+        || matches!(c, '々' | '〆' | 'ヶ' | '〇')
+}
+
+/// 各行のルビの位置を計算する。
+///
+/// 行の番号、ルビの文字列、行の中央からのルビの中央までの距離を返す。
+/// `is_vertical`の場合は、縦書きの高さで距離を測る。
+/// 行に含まれない (省略された) 親文字のルビは返さない。
+pub(crate) fn layout_ruby(
+    text: &str,
+    lines: &[String],
+    rubies: &[Ruby],
+    typography: &Typography,
+    is_vertical: bool,
+) -> Vec<(usize, String, f64)> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut result = Vec::new();
+
+    for (i, (line_start, line_end)) in locate_lines(text, lines).into_iter().enumerate() {
+        let line_width = measure_text_length(&lines[i], typography, is_vertical);
+        for ruby in rubies
+            .iter()
+            .filter(|ruby| line_start <= ruby.start && ruby.start < line_end)
+        {
+            let prefix: String = chars[line_start..ruby.start].iter().collect();
+            let base: String = chars[ruby.start..ruby.end.min(line_end)].iter().collect();
+            let center = -line_width * 0.5
+                + measure_text_length(&prefix, typography, is_vertical)
+                + measure_text_length(&base, typography, is_vertical) * 0.5;
+            result.push((i, ruby.text.clone(), center));
+        }
+    }
+
+    result
+}
+
+/// 各行が、`text`のどの範囲 (文字単位) を表示しているかを求める。
+///
+/// 省略記号は範囲に含めない。
+fn locate_lines(text: &str, lines: &[String]) -> Vec<(usize, usize)> {
+    let chars: Vec<char> = text.chars().collect();
+    let find = |from: usize, pattern: &[char]| {
+        (from..=chars.len()).find(|start| chars[*start..].starts_with(pattern))
+    };
+
+    let mut cursor = 0;
+    lines
+        .iter()
+        .map(|line| {
+            let line_chars: Vec<char> = line.chars().collect();
+            // 省略記号で切り詰められた行は、省略記号の前までを探す
+            let visible = match find(cursor, &line_chars) {
+                Some(_) => &line_chars[..],
+                None => line_chars.strip_suffix(&['…']).unwrap_or(&line_chars),
+            };
+            match find(cursor, visible) {
+                Some(start) => {
+                    cursor = start + visible.len();
+                    (start, cursor)
+                }
+                None => (cursor, cursor),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod parse_ruby {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            assert_eq!(parse_ruby("東京"), ("東京".to_string(), vec![]));
+            assert_eq!(
+                parse_ruby("｜東京《とうきょう》都"),
+                (
+                    "東京都".to_string(),
+                    vec![Ruby {
+                        start: 0,
+                        end: 2,
+                        text: "とうきょう".to_string()
+                    }]
+                )
+            );
+            // `｜`が無い場合は、直前の漢字の並びが親文字になる
+            assert_eq!(
+                parse_ruby("ここは東京《とうきょう》"),
+                (
+                    "ここは東京".to_string(),
+                    vec![Ruby {
+                        start: 3,
+                        end: 5,
+                        text: "とうきょう".to_string()
+                    }]
+                )
+            );
+            assert_eq!(
+                parse_ruby("|Tokyo《とうきょう》 大阪《おおさか》"),
+                (
+                    "Tokyo 大阪".to_string(),
+                    vec![
+                        Ruby {
+                            start: 0,
+                            end: 5,
+                            text: "とうきょう".to_string()
+                        },
+                        Ruby {
+                            start: 6,
+                            end: 8,
+                            text: "おおさか".to_string()
+                        }
+                    ]
+                )
+            );
+        }
+
+        #[test]
+        fn checking_value_when_not_ruby() {
+            assert_eq!(parse_ruby("a｜b"), ("a｜b".to_string(), vec![]));
+            assert_eq!(parse_ruby("《注》"), ("《注》".to_string(), vec![]));
+            assert_eq!(parse_ruby("東京《"), ("東京《".to_string(), vec![]));
+            // 閉じられていない記法では、`｜`も残す
+            assert_eq!(
+                parse_ruby("｜東京《とうきょう"),
+                ("｜東京《とうきょう".to_string(), vec![])
+            );
+            assert_eq!(parse_ruby("｜《注》"), ("｜《注》".to_string(), vec![]));
+            assert_eq!(
+                parse_ruby("a｜b｜東京《とうきょう》"),
+                (
+                    "a｜b東京".to_string(),
+                    vec![Ruby {
+                        start: 3,
+                        end: 5,
+                        text: "とうきょう".to_string()
+                    }]
+                )
+            );
+        }
+    }
+
+    mod layout_ruby {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            let typography = Typography::default();
+            let (text, rubies) = parse_ruby("東京《とうきょう》と大阪《おおさか》");
+            let lines = vec!["東京と".to_string(), "大阪".to_string()];
+            assert_eq!(
+                layout_ruby(&text, &lines, &rubies, &typography, false),
+                vec![
+                    (0, "とうきょう".to_string(), -5.0),
+                    (1, "おおさか".to_string(), 0.0)
+                ]
+            );
+
+            // 省略された親文字のルビは返さない
+            let lines = vec!["東京…".to_string()];
+            assert_eq!(
+                layout_ruby(&text, &lines, &rubies, &typography, false),
+                vec![(0, "とうきょう".to_string(), -3.0)]
+            );
+        }
+    }
+
+    mod locate_lines {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            let lines = vec!["ab".to_string(), "cd".to_string()];
+            assert_eq!(locate_lines("ab cd", &lines), vec![(0, 2), (3, 5)]);
+
+            let lines = vec!["ab".to_string(), "c…".to_string()];
+            assert_eq!(locate_lines("ab cde", &lines), vec![(0, 2), (3, 4)]);
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use std::f64::consts::{FRAC_PI_2, TAU};
+
+use svg::node::element::Group;
+
+use crate::category::{assign_colors, find_color};
+use crate::error::PieChartError;
+use crate::id::{create_id_prefix, validate_id_prefix};
+use crate::layout::separate_circles;
+use crate::options::ScatterPiesOptions;
+use crate::pie::create_pie;
+use crate::util::normalize_angle;
+
+/// 任意の位置に、ラベルの無い小さな円グラフを置く。
+pub(crate) fn create_scatter_pies<D, T>(
+    markers: &[(f64, f64, u32, D)],
+    options: &ScatterPiesOptions,
+) -> Result<Group, PieChartError>
+where
+    D: AsRef<[(T, f64)]>,
+    T: AsRef<str>,
+{
+    let colors = assign_colors(
+        markers
+            .iter()
+            .flat_map(|(_, _, _, dataset)| dataset.as_ref())
+            .map(|(category, _)| category.as_ref()),
+        &options.colors,
+    );
+    let id_prefix = match &options.id_prefix {
+        Some(id_prefix) => {
+            validate_id_prefix(id_prefix)?;
+            id_prefix.clone()
+        }
+        None => create_id_prefix(&describe_markers(markers, options)),
+    };
+
+    let mut circles: Vec<(f64, f64, f64)> = markers
+        .iter()
+        .map(|(x, y, radius, _)| (*x, *y, *radius as f64))
+        .collect();
+    if let Some(gap) = options.avoid_overlap {
+        separate_circles(&mut circles, gap as f64);
+    }
+
+    let mut group = Group::new();
+    // 接頭辞の末尾と番号が続かないよう、区切る
+    for (i, ((_, _, radius, dataset), (x, y, _))) in markers.iter().zip(circles).enumerate() {
+        group = group.add(create_marker(
+            (x, y),
+            *radius,
+            dataset.as_ref(),
+            &colors,
+            &format!("{id_prefix}-{i}-"),
+        )?);
+    }
+
+    Ok(group)
+}
+
+/// `center`を中心とする、1つの円グラフを作成する。
+///
+/// 座標を負にしないよう、円に外接する正方形の左上を原点として描き、移動する。
+fn create_marker<T>(
+    (x, y): (f64, f64),
+    radius: u32,
+    dataset: &[(T, f64)],
+    colors: &[(String, String)],
+    id_prefix: &str,
+) -> Result<Group, PieChartError>
+where
+    T: AsRef<str>,
+{
+    let mut group = Group::new();
+
+    let mut base_angle = FRAC_PI_2;
+    for (i, (category, ratio)) in dataset.iter().enumerate() {
+        let target_angle_range = TAU * ratio;
+        let pie = create_pie(
+            (radius, radius),
+            radius,
+            base_angle,
+            base_angle - target_angle_range,
+            base_angle - (target_angle_range * 0.5),
+            target_angle_range,
+            find_color(colors, category.as_ref()),
+            &format!("{id_prefix}p_{i}"),
+        )?;
+        group = group.add(pie);
+
+        base_angle = normalize_angle(base_angle - target_angle_range);
+    }
+
+    let radius = radius as f64;
+    Ok(group.set(
+        "transform",
+        format!("translate({}, {})", x - radius, y - radius),
+    ))
+}
+
+/// idの接頭辞を作成するための、円グラフの並びを表す文字列を作成する。
+fn describe_markers<D, T>(markers: &[(f64, f64, u32, D)], options: &ScatterPiesOptions) -> String
+where
+    D: AsRef<[(T, f64)]>,
+    T: AsRef<str>,
+{
+    let mut description = String::new();
+    for (x, y, radius, dataset) in markers {
+        description.push_str(&format!("{x:?}\0{y:?}\0{radius}\0"));
+        for (category, ratio) in dataset.as_ref() {
+            description.push_str(&format!("{}\0{ratio:?}\0", category.as_ref()));
+        }
+    }
+    description.push_str(&format!("{options:?}"));
+
+    description
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use svg::Document;
+
+    use super::*;
+
+    mod create_scatter_pies {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_data() {
+            let markers = [
+                (30.0, 40.0, 10, vec![("Red", 0.5), ("Blue", 0.5)]),
+                (80.0, 50.0, 15, vec![("Blue", 0.25), ("Green", 0.75)]),
+                (35.0, 40.0, 10, vec![("Green", 0.4), ("Red", 0.6)]),
+            ];
+            let options = ScatterPiesOptions {
+                id_prefix: Some("map".to_string()),
+                ..Default::default()
+            };
+
+            let text = create_scatter_pies(&markers, &options).unwrap().to_string();
+            // ラベルは描かない
+            assert!(!text.contains("<text"));
+            // 円ごとにidを区別する
+            assert!(text.contains(r#"id="map-0-p_0""#));
+            assert!(text.contains(r#"id="map-2-p_0""#));
+            // 同じカテゴリーは同じ色
+            assert_eq!(text.matches(r##"fill="#4e79a7""##).count(), 2);
+            assert_eq!(text.matches(r##"fill="#f28e2b""##).count(), 2);
+            assert!(text.contains(r#"<g transform="translate(20, 30)">"#));
+            assert!(text.contains(r#"<g transform="translate(65, 35)">"#));
+        }
+
+        #[test]
+        fn success_when_prefixes_in_one_page() {
+            let markers: Vec<_> = (0..11)
+                .map(|i| (i as f64 * 20.0, 10.0, 8, vec![("Red", 0.5), ("Blue", 0.5)]))
+                .collect();
+            let create_options = |id_prefix: &str| ScatterPiesOptions {
+                id_prefix: Some(id_prefix.to_string()),
+                ..Default::default()
+            };
+
+            // `map`の10番目と`map1`の0番目のidが重ならない
+            let page = format!(
+                "{}\n{}",
+                create_scatter_pies(&markers, &create_options("map")).unwrap(),
+                create_scatter_pies(&markers[..1], &create_options("map1")).unwrap()
+            );
+            let ids: Vec<&str> = page
+                .split(" id=\"")
+                .skip(1)
+                .map(|rest| rest.split('"').next().unwrap())
+                .collect();
+            let mut unique_ids = ids.clone();
+            unique_ids.sort();
+            unique_ids.dedup();
+            assert_eq!(ids.len(), unique_ids.len());
+            assert_eq!(ids.len(), 24);
+        }
+
+        #[test]
+        fn failure_when_invalid_id_prefix() {
+            let markers = [(30.0, 40.0, 10, vec![("Red", 0.5), ("Blue", 0.5)])];
+            let options = ScatterPiesOptions {
+                id_prefix: Some("map )".to_string()),
+                ..Default::default()
+            };
+
+            assert_eq!(
+                create_scatter_pies(&markers, &options).unwrap_err(),
+                PieChartError::InvalidIdPrefix("map )".to_string())
+            );
+        }
+
+        #[test]
+        fn success_when_avoiding_overlap() {
+            let markers = [
+                (30.0, 40.0, 10, vec![("Red", 0.5), ("Blue", 0.5)]),
+                (80.0, 50.0, 15, vec![("Blue", 0.25), ("Green", 0.75)]),
+                (35.0, 40.0, 10, vec![("Green", 0.4), ("Red", 0.6)]),
+            ];
+            let options = ScatterPiesOptions {
+                avoid_overlap: Some(2),
+                ..Default::default()
+            };
+
+            let group = create_scatter_pies(&markers, &options).unwrap();
+            let text = group.to_string();
+            // 重なった円を、左右へ離す
+            assert!(text.contains(r#"<g transform="translate(11.5, 30)">"#));
+            assert!(text.contains(r#"<g transform="translate(33.5, 30)">"#));
+            assert!(text.contains(r#"<g transform="translate(65, 35)">"#));
+            assert!(text.contains(r#"id="pie-"#));
+
+            let document = Document::new()
+                .set("viewBox", "0, 0, 120, 80")
+                .set("width", 120)
+                .set("height", 80)
+                .add(group);
+            let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("test_target/test_create_scatter_pies.svg");
+            svg::save(path, &document).unwrap();
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use svg::Document;
+
+use crate::options::{ChartSize, PhysicalUnit};
+
+/// 1インチのミリメートル。
+const MILLIMETERS_PER_INCH: f64 = 25.4;
+
+/// グラフの表示する大きさを設定する。
+pub(crate) fn apply_size(
+    document: Document,
+    width: u32,
+    height: u32,
+    size: &ChartSize,
+) -> Document {
+    match size {
+        ChartSize::Pixels => document.set("width", width).set("height", height),
+        ChartSize::Fluid {
+            preserve_aspect_ratio,
+        } => document
+            .set("width", "100%")
+            .set("height", "100%")
+            .set("preserveAspectRatio", preserve_aspect_ratio.as_str()),
+        ChartSize::Physical { unit, dpi } => document
+            .set("width", format_physical_length(width, *unit, *dpi))
+            .set("height", format_physical_length(height, *unit, *dpi)),
+    }
+}
+
+/// ピクセルの長さを、物理的な単位の長さにする。
+fn format_physical_length(pixels: u32, unit: PhysicalUnit, dpi: f64) -> String {
+    let inches = pixels as f64 / dpi;
+    let (length, suffix) = match unit {
+        PhysicalUnit::Millimeter => (inches * MILLIMETERS_PER_INCH, "mm"),
+        PhysicalUnit::Inch => (inches, "in"),
+    };
+    // 小数点以下3桁に丸める
+    format!("{}{suffix}", (length * 1000.0).round() / 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod apply_size {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            let document = apply_size(Document::new(), 200, 100, &ChartSize::Pixels);
+            assert!(document
+                .to_string()
+                .starts_with(r#"<svg height="100" width="200""#));
+
+            let size = ChartSize::Fluid {
+                preserve_aspect_ratio: "xMidYMid meet".to_string(),
+            };
+            let document = apply_size(Document::new(), 200, 100, &size);
+            assert!(document.to_string().starts_with(
+                r#"<svg height="100%" preserveAspectRatio="xMidYMid meet" width="100%""#
+            ));
+        }
+    }
+
+    mod format_physical_length {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            assert_eq!(
+                format_physical_length(300, PhysicalUnit::Millimeter, 300.0),
+                "25.4mm"
+            );
+            assert_eq!(
+                format_physical_length(100, PhysicalUnit::Millimeter, 96.0),
+                "26.458mm"
+            );
+            assert_eq!(
+                format_physical_length(150, PhysicalUnit::Inch, 300.0),
+                "0.5in"
+            );
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use svg::node::element::Circle;
+use svg::Document;
+
+use crate::options::SparkPieOptions;
+
+/// 描画に使う座標の大きさ。表示する大きさによらず、座標を短い数にする。
+const VIEW_BOX_SIZE: f64 = 20.0;
+/// 円周の長さとみなす値。割合を、そのまま百分率で破線の長さにする。
+const PATH_LENGTH: f64 = 100.0;
+/// 輪の幅の最小値。半径に対する割合で、これより細いと見えなくなる。
+const MIN_THICKNESS: f64 = 0.1;
+
+/// 1つの割合を、小さな円グラフで表す。
+///
+/// 太い線の円の`stroke-dasharray`で割合を描き、クリップパスやidを使わない。
+pub(crate) fn create_spark_pie(size: u32, ratio: f64, options: &SparkPieOptions) -> Document {
+    let center = VIEW_BOX_SIZE * 0.5;
+    // NaNも最小値にする
+    let thickness = if options.thickness.is_nan() {
+        MIN_THICKNESS
+    } else {
+        options.thickness.clamp(MIN_THICKNESS, 1.0)
+    };
+    let stroke_width = center * thickness;
+    // 線の中心に円を置き、線の外側を外接する正方形に合わせる
+    let radius = center - stroke_width * 0.5;
+    let ring = Circle::new()
+        .set("cx", center)
+        .set("cy", center)
+        .set("r", radius)
+        .set("fill", "none")
+        .set("stroke-width", stroke_width);
+
+    let mut document = Document::new()
+        .set("viewBox", format!("0, 0, {VIEW_BOX_SIZE}, {VIEW_BOX_SIZE}"))
+        .set("width", size)
+        .set("height", size);
+
+    if let Some(track) = &options.track {
+        document = document.add(ring.clone().set("stroke", track.as_str()));
+    }
+
+    if ratio.is_nan() || ratio <= 0.0 {
+        return document;
+    }
+    let mut value = ring.set("stroke", options.color.as_str());
+    if ratio < 1.0 {
+        // 上から時計回りに描く
+        let dash = (ratio * PATH_LENGTH * 100.0).round() / 100.0;
+        value = value
+            .set("pathLength", PATH_LENGTH)
+            .set("stroke-dasharray", format!("{dash} {PATH_LENGTH}"))
+            .set("transform", format!("rotate(-90, {center}, {center})"));
+    }
+
+    document.add(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    mod create_spark_pie {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            let text = create_spark_pie(16, 0.75, &SparkPieOptions::default()).to_string();
+            assert_eq!(
+                text,
+                concat!(
+                    r#"<svg height="16" viewBox="0, 0, 20, 20" width="16" xmlns="http://www.w3.org/2000/svg">"#,
+                    "\n",
+                    r##"<circle cx="10" cy="10" fill="none" r="5" stroke="#e0e0e0" stroke-width="10"/>"##,
+                    "\n",
+                    r##"<circle cx="10" cy="10" fill="none" pathLength="100" r="5" stroke="#4e79a7" stroke-dasharray="75 100" stroke-width="10" transform="rotate(-90, 10, 10)"/>"##,
+                    "\n</svg>"
+                )
+            );
+
+            // 割合が無い場合は、背景の輪だけ
+            let text = create_spark_pie(16, 0.0, &SparkPieOptions::default()).to_string();
+            assert_eq!(text.matches("<circle").count(), 1);
+
+            // 全体の場合は、破線にしない
+            let options = SparkPieOptions {
+                track: None,
+                thickness: 0.4,
+                ..Default::default()
+            };
+            let text = create_spark_pie(24, 1.0, &options).to_string();
+            assert!(text.contains(
+                r##"<circle cx="10" cy="10" fill="none" r="8" stroke="#4e79a7" stroke-width="4"/>"##
+            ));
+            assert!(!text.contains("stroke-dasharray"));
+
+            let text = create_spark_pie(24, 1.0 / 3.0, &options).to_string();
+            assert!(text.contains(r#"stroke-dasharray="33.33 100""#));
+        }
+
+        #[test]
+        fn checking_value_when_thin() {
+            // 細すぎる輪は、最小の幅にする
+            for thickness in [0.0, -1.0, f64::NAN] {
+                let options = SparkPieOptions {
+                    thickness,
+                    ..Default::default()
+                };
+                let text = create_spark_pie(16, 0.5, &options).to_string();
+                assert_eq!(text.matches(r#"r="9.5""#).count(), 2);
+                assert_eq!(text.matches(r#"stroke-width="1""#).count(), 2);
+            }
+        }
+
+        #[test]
+        fn success_when_many_in_page() {
+            let options = SparkPieOptions {
+                thickness: 0.5,
+                ..Default::default()
+            };
+            let charts: Vec<String> = (0..=10)
+                .map(|i| create_spark_pie(16, i as f64 / 10.0, &options).to_string())
+                .collect();
+            // 割合が0の場合は背景の輪だけ
+            let circle_count: usize = charts.iter().map(|c| c.matches("<circle").count()).sum();
+            assert_eq!(circle_count, 21);
+            for chart in &charts {
+                assert!(!chart.contains(" id="));
+                assert!(chart.len() < 512, "{} bytes: {chart}", chart.len());
+            }
+
+            let rows: String = charts
+                .iter()
+                .enumerate()
+                .map(|(i, chart)| format!("<tr><td>{}%</td><td>{chart}</td></tr>\n", i * 10))
+                .collect();
+            let html = format!(
+                "<!DOCTYPE html>\n<html>\n<body>\n<table>\n{rows}</table>\n</body>\n</html>\n"
+            );
+
+            let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("test_target/test_create_spark_pie.html");
+            std::fs::write(path, html).unwrap();
+        }
+    }
+}
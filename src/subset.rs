@@ -0,0 +1,277 @@
+use std::collections::BTreeSet;
+
+use ttf_parser::Tag;
+
+use crate::error::PieChartError;
+use crate::font::Font;
+
+/// 部分化したフォントで、意味をなさなくなるため削除する表。
+const DROPPED_TABLES: [&[u8; 4]; 4] = [b"DSIG", b"hdmx", b"LTSH", b"VDMX"];
+
+/// `text`に使われている文字のグリフだけを残したフォントを作成する。
+///
+/// グリフIDを変えないよう、使われていないグリフを空にする。
+/// `glyf`の表を持つTrueTypeのフォントのみに対応する。
+pub(crate) fn subset_font(font: &Font, text: &str) -> Result<Vec<u8>, PieChartError> {
+    let face = font.face();
+    let raw_face = face.raw_face();
+    let table = |tag: &[u8; 4]| raw_face.table(Tag::from_bytes(tag));
+
+    let (Some(head), Some(loca), Some(glyf)) = (table(b"head"), table(b"loca"), table(b"glyf"))
+    else {
+        return Err(PieChartError::FontNotSubsettable);
+    };
+    let number_of_glyphs = face.number_of_glyphs();
+    let is_long_loca = read_u16(head, 50).ok_or(PieChartError::InvalidFont)? == 1;
+    let glyph_data = |id: u16| -> Option<&[u8]> {
+        let (start, end) = if is_long_loca {
+            (
+                read_u32(loca, id as usize * 4)? as usize,
+                read_u32(loca, id as usize * 4 + 4)? as usize,
+            )
+        } else {
+            (
+                read_u16(loca, id as usize * 2)? as usize * 2,
+                read_u16(loca, id as usize * 2 + 2)? as usize * 2,
+            )
+        };
+        glyf.get(start..end)
+    };
+
+    // 使われているグリフと、複合グリフの部品を集める。
+    let mut used_glyphs = BTreeSet::new();
+    let mut pending: Vec<u16> = std::iter::once(0)
+        .chain(
+            text.chars()
+                .filter_map(|c| face.glyph_index(c))
+                .map(|id| id.0),
+        )
+        .collect();
+    while let Some(id) = pending.pop() {
+        if id >= number_of_glyphs || !used_glyphs.insert(id) {
+            continue;
+        }
+        if let Some(data) = glyph_data(id) {
+            pending.extend(composite_components(data));
+        }
+    }
+
+    let mut new_glyf = Vec::new();
+    let mut new_loca = Vec::new();
+    for id in 0..number_of_glyphs {
+        new_loca.extend((new_glyf.len() as u32).to_be_bytes());
+        if used_glyphs.contains(&id) {
+            new_glyf.extend(glyph_data(id).ok_or(PieChartError::InvalidFont)?);
+            pad(&mut new_glyf);
+        }
+    }
+    new_loca.extend((new_glyf.len() as u32).to_be_bytes());
+
+    let mut new_head = head.to_vec();
+    // checkSumAdjustmentは後で計算し、locaは常に長い形式にする。
+    new_head[8..12].copy_from_slice(&[0; 4]);
+    new_head[50..52].copy_from_slice(&1u16.to_be_bytes());
+
+    let tables: Vec<([u8; 4], Vec<u8>)> = raw_face
+        .table_records
+        .into_iter()
+        .filter_map(|record| {
+            let tag = record.tag.to_bytes();
+            if DROPPED_TABLES.contains(&&tag) {
+                return None;
+            }
+            let data = match &tag {
+                b"head" => new_head.clone(),
+                b"loca" => new_loca.clone(),
+                b"glyf" => new_glyf.clone(),
+                _ => table(&tag)?.to_vec(),
+            };
+            Some((tag, data))
+        })
+        .collect();
+
+    Ok(write_font(tables))
+}
+
+/// 複合グリフの部品のグリフIDを取得する。
+fn composite_components(data: &[u8]) -> Vec<u16> {
+    const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+    const WE_HAVE_A_SCALE: u16 = 0x0008;
+    const MORE_COMPONENTS: u16 = 0x0020;
+    const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+    const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+
+    let mut components = Vec::new();
+    // numberOfContoursが負の場合が複合グリフ
+    if data.len() < 10 || (read_u16(data, 0).unwrap_or(0) as i16) >= 0 {
+        return components;
+    }
+
+    let mut offset = 10;
+    while let (Some(flags), Some(id)) = (read_u16(data, offset), read_u16(data, offset + 2)) {
+        components.push(id);
+        offset += 4;
+        offset += if flags & ARG_1_AND_2_ARE_WORDS != 0 {
+            4
+        } else {
+            2
+        };
+        offset += if flags & WE_HAVE_A_SCALE != 0 {
+            2
+        } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+            4
+        } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+            8
+        } else {
+            0
+        };
+        if flags & MORE_COMPONENTS == 0 {
+            break;
+        }
+    }
+
+    components
+}
+
+/// 表からフォントのデータを作成する。
+fn write_font(mut tables: Vec<([u8; 4], Vec<u8>)>) -> Vec<u8> {
+    tables.sort_by_key(|(tag, _)| *tag);
+
+    let number_of_tables = tables.len() as u16;
+    let entry_selector = (number_of_tables.max(1)).ilog2() as u16;
+    let search_range = (1u16 << entry_selector) * 16;
+    let range_shift = number_of_tables * 16 - search_range;
+
+    let mut font = Vec::new();
+    font.extend(0x0001_0000u32.to_be_bytes());
+    for v in [number_of_tables, search_range, entry_selector, range_shift] {
+        font.extend(v.to_be_bytes());
+    }
+
+    let mut offset = 12 + 16 * tables.len();
+    let mut head_offset = None;
+    let mut body = Vec::new();
+    for (tag, data) in tables.iter() {
+        if tag == b"head" {
+            head_offset = Some(offset);
+        }
+        font.extend(tag);
+        font.extend(calc_checksum(data).to_be_bytes());
+        font.extend((offset as u32).to_be_bytes());
+        font.extend((data.len() as u32).to_be_bytes());
+        body.extend(data);
+        pad(&mut body);
+        offset = 12 + 16 * tables.len() + body.len();
+    }
+    font.extend(body);
+
+    if let Some(head_offset) = head_offset {
+        let adjustment = 0xB1B0_AFBAu32.wrapping_sub(calc_checksum(&font));
+        font[head_offset + 8..head_offset + 12].copy_from_slice(&adjustment.to_be_bytes());
+    }
+
+    font
+}
+
+fn calc_checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut bytes = [0u8; 4];
+        bytes[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(bytes))
+    })
+}
+
+fn pad(data: &mut Vec<u8>) {
+    while !data.len().is_multiple_of(4) {
+        data.push(0);
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::font::tests::create_test_font;
+
+    mod subset_font {
+        use ttf_parser::{Face, GlyphId};
+
+        use super::*;
+
+        struct NullBuilder;
+
+        impl ttf_parser::OutlineBuilder for NullBuilder {
+            fn move_to(&mut self, _: f32, _: f32) {}
+            fn line_to(&mut self, _: f32, _: f32) {}
+            fn quad_to(&mut self, _: f32, _: f32, _: f32, _: f32) {}
+            fn curve_to(&mut self, _: f32, _: f32, _: f32, _: f32, _: f32, _: f32) {}
+            fn close(&mut self) {}
+        }
+
+        #[test]
+        fn success_when_composite_glyph() {
+            let data = create_test_font();
+            let font = Font::from_bytes(data.clone()).unwrap();
+            let subset = subset_font(&font, "B").unwrap();
+            assert!(subset.len() < data.len());
+
+            let face = Face::parse(&subset, 0).unwrap();
+            let has_outline = |id: u16| face.outline_glyph(GlyphId(id), &mut NullBuilder).is_some();
+            // 複合グリフとその部品は残る
+            assert!(has_outline(4));
+            assert!(has_outline(1));
+            // 使われていないグリフは空になる
+            assert!(!has_outline(2));
+            assert!(!has_outline(5));
+            // 文字とグリフの対応と送り幅は変わらない
+            assert_eq!(face.glyph_index('東'), Some(GlyphId(2)));
+            assert_eq!(face.glyph_hor_advance(GlyphId(2)), Some(1000));
+
+            // 全体のチェックサムは0xB1B0AFBAになる
+            assert_eq!(calc_checksum(&subset), 0xB1B0_AFBA);
+        }
+    }
+
+    mod composite_components {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            let mut data = Vec::new();
+            for v in [-1i16, 0, 0, 0, 0] {
+                data.extend(v.to_be_bytes());
+            }
+            // MORE_COMPONENTS, 1番、引数はバイト
+            data.extend([0x00, 0x20, 0x00, 0x01, 0x00, 0x00]);
+            // ARG_1_AND_2_ARE_WORDS | WE_HAVE_A_SCALE, 3番
+            data.extend([0x00, 0x09, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x40, 0x00]);
+            assert_eq!(composite_components(&data), vec![1, 3]);
+
+            let mut simple = Vec::new();
+            for v in [1i16, 0, 0, 0, 0] {
+                simple.extend(v.to_be_bytes());
+            }
+            assert!(composite_components(&simple).is_empty());
+        }
+    }
+
+    mod calc_checksum {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            assert_eq!(calc_checksum(&[]), 0);
+            assert_eq!(calc_checksum(&[0, 0, 0, 1, 0, 0, 0, 2]), 3);
+            assert_eq!(calc_checksum(&[1]), 0x0100_0000);
+        }
+    }
+}
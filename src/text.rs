@@ -0,0 +1,547 @@
+use crate::options::Typography;
+
+const ELLIPSIS: char = '…';
+
+/// 行頭に置かない文字 (行頭禁則)。
+const NO_BREAK_BEFORE: &str = "、。，．,.)）]］}｝〕〉》」』】〙〗〟’”｠»ゝゞーァィゥェォッャュョヮヵヶぁぃぅぇぉっゃゅょゎゕゖㇰㇱㇲㇳㇴㇵㇶㇷㇸㇹㇺㇻㇼㇽㇾㇿ々〻‐゠–〜～?!？！‼⁇⁈⁉・:;：；/／";
+/// 行末に置かない文字 (行末禁則)。
+const NO_BREAK_AFTER: &str = "([{（［｛〔〈《「『【〘〖〝‘“｟«";
+
+/// 文字列の幅を計測する。
+pub(crate) fn measure_text_width(text: &str, typography: &Typography) -> f64 {
+    measure_char_widths(text, typography).iter().sum()
+}
+
+/// 文字ごとの幅を計測する。字間も含む。
+///
+/// フォントがある場合はグリフの送り幅を、無い場合は見積もりを使う。
+pub(crate) fn measure_char_widths(text: &str, typography: &Typography) -> Vec<f64> {
+    let size = typography.size as f64;
+
+    #[cfg(feature = "font")]
+    let widths = match &typography.font {
+        Some(font) => font.char_widths(text, size),
+        None => estimate_char_widths(text, size),
+    };
+    #[cfg(not(feature = "font"))]
+    let widths = estimate_char_widths(text, size);
+
+    let letter_spacing = typography.letter_spacing.unwrap_or(0.0);
+    widths.into_iter().map(|w| w + letter_spacing).collect()
+}
+
+/// 文字列の、文字を並べる方向の長さを計測する。
+///
+/// `is_vertical`の場合は縦書きの高さ、それ以外は幅。
+pub(crate) fn measure_text_length(text: &str, typography: &Typography, is_vertical: bool) -> f64 {
+    measure_char_advances(text, typography, is_vertical)
+        .iter()
+        .sum()
+}
+
+/// 文字ごとの、文字を並べる方向に進める長さを計測する。字間も含む。
+///
+/// 縦書きでは、文字を全て正立させて全角の高さで送る。
+/// 縦中横にする数字は、まとめて1文字分とする。
+pub(crate) fn measure_char_advances(
+    text: &str,
+    typography: &Typography,
+    is_vertical: bool,
+) -> Vec<f64> {
+    if !is_vertical {
+        return measure_char_widths(text, typography);
+    }
+
+    let advance = typography.size as f64 + typography.letter_spacing.unwrap_or(0.0);
+    split_upright_digits(text)
+        .into_iter()
+        .flat_map(|(run, is_upright)| {
+            run.chars()
+                .enumerate()
+                .map(|(i, _)| if is_upright && i > 0 { 0.0 } else { advance })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// 縦中横にする数字の最大の桁数。
+const UPRIGHT_DIGITS_MAX_LENGTH: usize = 2;
+
+/// 縦書きの文字列を、縦中横にする短い数字とそれ以外に分ける。
+///
+/// 縦中横にする場合は`true`を返す。
+pub(crate) fn split_upright_digits(text: &str) -> Vec<(String, bool)> {
+    let mut runs: Vec<(String, bool)> = Vec::new();
+    for c in text.chars() {
+        let is_digit = c.is_ascii_digit();
+        match runs.last_mut() {
+            Some((run, last_is_digit)) if *last_is_digit == is_digit => run.push(c),
+            _ => runs.push((c.to_string(), is_digit)),
+        }
+    }
+
+    let mut result: Vec<(String, bool)> = Vec::new();
+    for (run, is_digit) in runs {
+        let is_upright = is_digit && run.chars().count() <= UPRIGHT_DIGITS_MAX_LENGTH;
+        match result.last_mut() {
+            // 長い数字は前後とまとめ、縦中横にしない
+            Some((last, false)) if !is_upright => last.push_str(&run),
+            _ => result.push((run, is_upright)),
+        }
+    }
+
+    result
+}
+
+fn estimate_char_widths(text: &str, size: f64) -> Vec<f64> {
+    text.chars().map(|c| estimate_char_width(c, size)).collect()
+}
+
+/// 文字の幅を大まかに見積もる。
+///
+/// 全角の文字は`size`、それ以外は`size`の0.6倍の幅とする。
+pub(crate) fn estimate_char_width(c: char, size: f64) -> f64 {
+    if is_wide_char(c) {
+        size
+    } else {
+        size * 0.6
+    }
+}
+
+/// 全角 (East Asian Wide) の文字かどうか。
+pub(crate) fn is_wide_char(c: char) -> bool {
+    matches!(c as u32,
+        0x1100..=0x115F
+        | 0x2E80..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x20000..=0x3FFFD)
+}
+
+/// `max_width`に収まるよう、文字列を省略記号付きで切り詰める。
+///
+/// `is_vertical`の場合は、縦書きの高さで`max_width`と比べる。
+/// 省略記号すら収まらない場合は`None`を返す。
+pub(crate) fn ellipsize(
+    text: &str,
+    typography: &Typography,
+    max_width: f64,
+    is_vertical: bool,
+) -> Option<String> {
+    let widths = measure_char_advances(text, typography, is_vertical);
+    if widths.iter().sum::<f64>() <= max_width {
+        return Some(text.to_string());
+    }
+
+    let mut width = measure_text_length(&ELLIPSIS.to_string(), typography, is_vertical);
+    if width > max_width {
+        return None;
+    }
+
+    let mut result = String::new();
+    for (c, char_width) in text.chars().zip(widths) {
+        width += char_width;
+        if width > max_width {
+            break;
+        }
+        result.push(c);
+    }
+    result.push(ELLIPSIS);
+
+    Some(result)
+}
+
+/// `max_width`に収まるよう、文字列を行に分ける。
+///
+/// 空白の後と、全角の文字の前後で改行する。ただし、禁則の文字の前後では改行しない。
+/// 1語で収まらない場合は、文字の途中で改行する。
+/// `max_lines`を超える場合は、最後の行を省略記号付きで切り詰める。
+/// `max_lines`が0の場合は、1行とみなす。
+/// `is_vertical`の場合は、縦書きの高さで`max_width`と比べる。
+pub(crate) fn wrap_text(
+    text: &str,
+    typography: &Typography,
+    max_width: f64,
+    max_lines: Option<usize>,
+    is_vertical: bool,
+) -> Vec<String> {
+    // 各行の、`text`の中の開始位置と終了位置
+    let mut lines: Vec<(usize, usize)> = Vec::new();
+    let mut line_start = 0;
+    let mut line_end = 0;
+    for (start, end) in split_breakable(text) {
+        let candidate = text[line_start..end].trim_end();
+        if line_end == line_start
+            || measure_text_length(candidate, typography, is_vertical) <= max_width
+        {
+            line_end = end;
+            continue;
+        }
+        lines.push((line_start, line_end));
+        line_start = start;
+        line_end = end;
+    }
+    if line_start < text.len() {
+        lines.push((line_start, text.len()));
+    }
+
+    // 1語で収まらない行を、文字の途中で改行する
+    let lines: Vec<(usize, usize)> = lines
+        .into_iter()
+        .flat_map(|(start, end)| {
+            split_by_width(text, start, end, typography, max_width, is_vertical)
+        })
+        .collect();
+
+    let mut result: Vec<String> = lines
+        .iter()
+        .map(|(start, end)| text[*start..*end].trim_end().to_string())
+        .collect();
+    // 0行では何も描かれないため、少なくとも1行は残す
+    let max_lines = max_lines.map(|max_lines| max_lines.max(1));
+    if let Some(max_lines) = max_lines.filter(|max_lines| result.len() > *max_lines) {
+        result.truncate(max_lines);
+        if let Some(last) = result.last_mut() {
+            let rest = text[lines[max_lines - 1].0..].trim_end();
+            *last = ellipsize_forcibly(rest, typography, max_width, is_vertical);
+        }
+    }
+
+    result
+}
+
+/// 改行できる位置で文字列を分け、それぞれの開始位置と終了位置を返す。
+fn split_breakable(text: &str) -> Vec<(usize, usize)> {
+    let mut segments = Vec::new();
+    let mut segment_start = 0;
+    let mut prev: Option<char> = None;
+    for (i, c) in text.char_indices() {
+        if prev.is_some_and(|prev| can_break_between(prev, c)) {
+            segments.push((segment_start, i));
+            segment_start = i;
+        }
+        prev = Some(c);
+    }
+    if segment_start < text.len() {
+        segments.push((segment_start, text.len()));
+    }
+
+    segments
+}
+
+/// `prev`と`next`の間で改行できるかどうか。
+fn can_break_between(prev: char, next: char) -> bool {
+    if next.is_whitespace() || NO_BREAK_BEFORE.contains(next) || NO_BREAK_AFTER.contains(prev) {
+        false
+    } else {
+        prev.is_whitespace() || prev == '-' || is_wide_char(prev) || is_wide_char(next)
+    }
+}
+
+/// `text`の`start`から`end`を、`max_width`に収まるよう文字の途中で分ける。
+fn split_by_width(
+    text: &str,
+    start: usize,
+    end: usize,
+    typography: &Typography,
+    max_width: f64,
+    is_vertical: bool,
+) -> Vec<(usize, usize)> {
+    let line = &text[start..end];
+    let widths = measure_char_advances(line.trim_end(), typography, is_vertical);
+
+    let mut result = Vec::new();
+    let mut line_start = start;
+    let mut width = 0.0;
+    for ((i, _), char_width) in line.char_indices().zip(widths) {
+        let i = start + i;
+        if width + char_width > max_width && i > line_start {
+            result.push((line_start, i));
+            line_start = i;
+            width = 0.0;
+        }
+        width += char_width;
+    }
+    result.push((line_start, end));
+
+    result
+}
+
+/// 収まるかどうかに関わらず、省略記号付きで切り詰める。
+fn ellipsize_forcibly(
+    text: &str,
+    typography: &Typography,
+    max_width: f64,
+    is_vertical: bool,
+) -> String {
+    match ellipsize(text, typography, max_width, is_vertical) {
+        Some(ellipsized) if ellipsized != text => ellipsized,
+        _ => {
+            let ellipsis_width =
+                measure_text_length(&ELLIPSIS.to_string(), typography, is_vertical);
+            let mut result = ellipsize(text, typography, max_width - ellipsis_width, is_vertical)
+                .unwrap_or_default()
+                .trim_end_matches(ELLIPSIS)
+                .to_string();
+            result.push(ELLIPSIS);
+            result
+        }
+    }
+}
+
+/// 基準線から、`dominant_baseline`の位置までの距離を計算する。
+pub(crate) fn calc_baseline_shift(dominant_baseline: &str, ascender: f64, descender: f64) -> f64 {
+    match dominant_baseline {
+        "central" | "middle" => (ascender + descender) * 0.5,
+        "hanging" | "text-before-edge" => ascender,
+        "ideographic" | "text-after-edge" => descender,
+        _ => 0.0,
+    }
+}
+
+/// 文字の高さ (基準線からのascenderとdescender) を計測する。
+///
+/// フォントがある場合はフォントの値を、無い場合は`size`の0.8倍と-0.2倍を使う。
+pub(crate) fn measure_vertical_metrics(typography: &Typography) -> (f64, f64) {
+    let size = typography.size as f64;
+
+    #[cfg(feature = "font")]
+    if let Some(font) = &typography.font {
+        let face = font.face();
+        let scale = size / face.units_per_em() as f64;
+        return (
+            face.ascender() as f64 * scale,
+            face.descender() as f64 * scale,
+        );
+    }
+
+    (size * 0.8, size * -0.2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod measure_text_width {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            let typography = Typography::default();
+            assert_eq!(measure_text_width("", &typography), 0.0);
+            assert_eq!(measure_text_width("ab", &typography), 12.0);
+            assert_eq!(measure_text_width("東京", &typography), 20.0);
+            assert_eq!(measure_text_width("ＡB", &typography), 16.0);
+
+            let typography = Typography {
+                letter_spacing: Some(1.0),
+                ..Default::default()
+            };
+            assert_eq!(measure_text_width("ab", &typography), 14.0);
+        }
+
+        #[cfg(feature = "font")]
+        #[test]
+        fn checking_value_with_font() {
+            use crate::font::{tests::create_test_font, Font};
+
+            let typography = Typography {
+                font: Some(Font::from_bytes(create_test_font()).unwrap()),
+                ..Default::default()
+            };
+            assert_eq!(measure_text_width("A東", &typography), 16.0);
+        }
+    }
+
+    mod ellipsize {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            let typography = Typography::default();
+            assert_eq!(
+                ellipsize("abc", &typography, 100.0, false),
+                Some("abc".to_string())
+            );
+            assert_eq!(
+                ellipsize("abcdef", &typography, 30.0, false),
+                Some("abcd…".to_string())
+            );
+            assert_eq!(
+                ellipsize("東京都庁", &typography, 30.0, false),
+                Some("東京…".to_string())
+            );
+            assert_eq!(ellipsize("abc", &typography, 5.0, false), None);
+            // 縦書きでは、全角の高さで測る
+            assert_eq!(
+                ellipsize("abcdef", &typography, 30.0, true),
+                Some("ab…".to_string())
+            );
+        }
+    }
+
+    mod wrap_text {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            let typography = Typography::default();
+            assert_eq!(
+                wrap_text("abc", &typography, 100.0, None, false),
+                vec!["abc"]
+            );
+            assert_eq!(
+                wrap_text("Other operating expenses", &typography, 60.0, None, false),
+                vec!["Other", "operating", "expenses"]
+            );
+            assert_eq!(
+                wrap_text("ab cd ef", &typography, 30.0, None, false),
+                vec!["ab cd", "ef"]
+            );
+            assert_eq!(
+                wrap_text("abcdefgh", &typography, 30.0, None, false),
+                vec!["abcde", "fgh"]
+            );
+        }
+
+        #[test]
+        fn checking_value_with_kinsoku() {
+            let typography = Typography::default();
+            assert_eq!(
+                wrap_text("東京都庁", &typography, 30.0, None, false),
+                vec!["東京都", "庁"]
+            );
+            // 句読点や閉じ括弧は行頭に、開き括弧は行末に置かない
+            assert_eq!(
+                wrap_text("東京、大阪。", &typography, 30.0, None, false),
+                vec!["東京、", "大阪。"]
+            );
+            assert_eq!(
+                wrap_text("売上（東京）", &typography, 30.0, None, false),
+                vec!["売上", "（東", "京）"]
+            );
+        }
+
+        #[test]
+        fn checking_value_with_max_lines() {
+            let typography = Typography::default();
+            assert_eq!(
+                wrap_text(
+                    "Other operating expenses",
+                    &typography,
+                    60.0,
+                    Some(2),
+                    false
+                ),
+                vec!["Other", "operating…"]
+            );
+            assert_eq!(
+                wrap_text("ab cd ef", &typography, 100.0, Some(1), false),
+                vec!["ab cd ef"]
+            );
+            assert_eq!(
+                wrap_text(
+                    "Other operating expenses",
+                    &typography,
+                    60.0,
+                    Some(0),
+                    false
+                ),
+                vec!["Other ope…"]
+            );
+        }
+    }
+
+    mod measure_char_advances {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            let typography = Typography::default();
+            assert_eq!(
+                measure_char_advances("Ab", &typography, false),
+                vec![6.0, 6.0]
+            );
+            // 縦書きでは全角の高さで送り、縦中横の数字は1文字分
+            assert_eq!(
+                measure_char_advances("Ab", &typography, true),
+                vec![10.0, 10.0]
+            );
+            assert_eq!(
+                measure_char_advances("第12回", &typography, true),
+                vec![10.0, 10.0, 0.0, 10.0]
+            );
+            assert_eq!(measure_text_length("第100回", &typography, true), 50.0);
+        }
+    }
+
+    mod split_upright_digits {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            assert_eq!(
+                split_upright_digits("東京"),
+                vec![("東京".to_string(), false)]
+            );
+            assert_eq!(
+                split_upright_digits("12月3日"),
+                vec![
+                    ("12".to_string(), true),
+                    ("月".to_string(), false),
+                    ("3".to_string(), true),
+                    ("日".to_string(), false),
+                ]
+            );
+            // 3桁以上の数字は縦中横にしない
+            assert_eq!(
+                split_upright_digits("第100回"),
+                vec![("第100回".to_string(), false)]
+            );
+        }
+    }
+
+    mod can_break_between {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            assert!(can_break_between(' ', 'a'));
+            assert!(!can_break_between('a', ' '));
+            assert!(!can_break_between('a', 'b'));
+            assert!(can_break_between('-', 'b'));
+            assert!(can_break_between('東', '京'));
+            assert!(can_break_between('a', '京'));
+            assert!(!can_break_between('京', '。'));
+            assert!(!can_break_between('京', '）'));
+            assert!(!can_break_between('（', '京'));
+        }
+    }
+
+    mod calc_baseline_shift {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            assert_eq!(calc_baseline_shift("auto", 8.0, -2.0), 0.0);
+            assert_eq!(calc_baseline_shift("central", 8.0, -2.0), 3.0);
+            assert_eq!(calc_baseline_shift("hanging", 8.0, -2.0), 8.0);
+            assert_eq!(calc_baseline_shift("ideographic", 8.0, -2.0), -2.0);
+        }
+    }
+
+    mod measure_vertical_metrics {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            assert_eq!(
+                measure_vertical_metrics(&Typography::default()),
+                (8.0, -2.0)
+            );
+        }
+    }
+}
@@ -0,0 +1,242 @@
+use svg::Node;
+
+use crate::options::Typography;
+
+/// CSSの総称フォントファミリー。
+const GENERIC_FAMILIES: [&str; 13] = [
+    "serif",
+    "sans-serif",
+    "monospace",
+    "cursive",
+    "fantasy",
+    "system-ui",
+    "ui-serif",
+    "ui-sans-serif",
+    "ui-monospace",
+    "ui-rounded",
+    "emoji",
+    "math",
+    "fangsong",
+];
+
+/// 引用符無しではフォントファミリー名にできないキーワード。
+const CSS_WIDE_KEYWORDS: [&str; 6] = [
+    "inherit",
+    "initial",
+    "unset",
+    "default",
+    "revert",
+    "revert-layer",
+];
+
+/// 要素に文字の設定を反映する。
+pub(crate) fn apply_typography<T>(mut element: T, typography: &Typography) -> T
+where
+    T: Node,
+{
+    let font_family = format_font_family(&typography.font_family);
+    if !font_family.is_empty() {
+        element.assign("font-family", font_family);
+    }
+    element.assign("font-size", typography.size);
+    if let Some(weight) = &typography.weight {
+        element.assign("font-weight", weight.as_str());
+    }
+    if let Some(style) = &typography.style {
+        element.assign("font-style", style.as_str());
+    }
+    if let Some(letter_spacing) = typography.letter_spacing {
+        element.assign("letter-spacing", letter_spacing);
+    }
+    if let Some(dominant_baseline) = &typography.dominant_baseline {
+        element.assign("dominant-baseline", dominant_baseline.as_str());
+    }
+
+    element
+}
+
+/// 文字列の向きを設定する。
+///
+/// 右から左へ書く文字列と、右から左へ書くグラフの中の文字列に設定する。
+pub(crate) fn apply_direction<T>(mut element: T, is_rtl: bool, is_chart_rtl: bool) -> T
+where
+    T: Node,
+{
+    if is_rtl || is_chart_rtl {
+        element.assign("direction", if is_rtl { "rtl" } else { "ltr" });
+        element.assign("unicode-bidi", "embed");
+    }
+
+    element
+}
+
+/// フォントの一覧を、必要な場合のみ引用符で囲んで`font-family`の値にする。
+pub(crate) fn format_font_family(font_stack: &str) -> String {
+    split_font_stack(font_stack)
+        .iter()
+        .map(|family| format_family(family))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// 引用符の中のカンマを無視して、フォントの一覧を分割する。
+fn split_font_stack(font_stack: &str) -> Vec<String> {
+    let mut families = Vec::new();
+    let mut current = String::new();
+    let mut quote = None;
+
+    for c in font_stack.chars() {
+        match (quote, c) {
+            // 引用符は名前の先頭にある場合のみ扱う
+            (None, '"' | '\'') if current.trim().is_empty() => {
+                quote = Some(c);
+                current.push(c);
+            }
+            (Some(q), _) if q == c => {
+                quote = None;
+                current.push(c);
+            }
+            (None, ',') => families.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    families.push(current);
+
+    families
+        .into_iter()
+        .map(|family| family.trim().to_string())
+        .filter(|family| !family.is_empty())
+        .collect()
+}
+
+fn format_family(family: &str) -> String {
+    let is_quoted = family.len() >= 2
+        && ((family.starts_with('"') && family.ends_with('"'))
+            || (family.starts_with('\'') && family.ends_with('\'')));
+    if is_quoted || GENERIC_FAMILIES.contains(&family.to_ascii_lowercase().as_str()) {
+        return family.to_string();
+    }
+
+    let words: Vec<&str> = family.split_whitespace().collect();
+    let needs_quote = words.iter().any(|word| {
+        !is_identifier(word) || CSS_WIDE_KEYWORDS.contains(&word.to_ascii_lowercase().as_str())
+    });
+
+    if needs_quote {
+        format!("'{}'", family.replace('\\', "\\\\").replace('\'', "\\'"))
+    } else {
+        words.join(" ")
+    }
+}
+
+/// CSSの識別子として有効かどうか。
+fn is_identifier(word: &str) -> bool {
+    let is_name_start = |c: char| c.is_ascii_alphabetic() || c == '_' || !c.is_ascii();
+    let is_name = |c: char| is_name_start(c) || c.is_ascii_digit() || c == '-';
+
+    let mut chars = word.chars();
+    let first_is_valid = match chars.next() {
+        Some('-') => chars.next().is_some_and(is_name_start),
+        Some(c) => is_name_start(c),
+        None => false,
+    };
+
+    first_is_valid && chars.all(is_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod apply_typography {
+        use svg::node::element::Text;
+
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            let typography = Typography {
+                font_family: "\"Noto Sans JP\", sans-serif".to_string(),
+                size: 12,
+                weight: Some("bold".to_string()),
+                style: Some("italic".to_string()),
+                letter_spacing: Some(0.5),
+                dominant_baseline: Some("central".to_string()),
+                ..Default::default()
+            };
+            let text = apply_typography(Text::new("label"), &typography).to_string();
+            assert!(text.contains(r#"font-family="&quot;Noto Sans JP&quot;, sans-serif""#));
+            assert!(text.contains(r#"font-size="12""#));
+            assert!(text.contains(r#"font-weight="bold""#));
+            assert!(text.contains(r#"font-style="italic""#));
+            assert!(text.contains(r#"letter-spacing="0.5""#));
+            assert!(text.contains(r#"dominant-baseline="central""#));
+
+            let typography = Typography {
+                font_family: String::new(),
+                ..Default::default()
+            };
+            let text = apply_typography(Text::new("label"), &typography).to_string();
+            assert!(!text.contains("font-family"));
+            assert!(!text.contains("font-weight"));
+        }
+    }
+
+    mod apply_direction {
+        use svg::node::element::Text;
+
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            let text = apply_direction(Text::new("label"), false, false).to_string();
+            assert!(!text.contains("direction"));
+
+            let text = apply_direction(Text::new("label"), true, false).to_string();
+            assert!(text.contains(r#"direction="rtl" unicode-bidi="embed""#));
+
+            let text = apply_direction(Text::new("label"), false, true).to_string();
+            assert!(text.contains(r#"direction="ltr" unicode-bidi="embed""#));
+        }
+    }
+
+    mod format_font_family {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            assert_eq!(format_font_family("sans-serif"), "sans-serif");
+            assert_eq!(
+                format_font_family("\"Noto Sans JP\", sans-serif"),
+                "\"Noto Sans JP\", sans-serif"
+            );
+            assert_eq!(
+                format_font_family("Noto Sans JP,serif"),
+                "Noto Sans JP, serif"
+            );
+            assert_eq!(format_font_family("游ゴシック"), "游ゴシック");
+            assert_eq!(format_font_family("ＭＳ ゴシック"), "ＭＳ ゴシック");
+            assert_eq!(format_font_family("M+ 1p"), "'M+ 1p'");
+            assert_eq!(format_font_family("Font 2000"), "'Font 2000'");
+            assert_eq!(format_font_family("inherit"), "'inherit'");
+            assert_eq!(format_font_family("It's, a"), "'It\\'s', a");
+            assert_eq!(format_font_family("'A, B', C"), "'A, B', C");
+            assert_eq!(format_font_family(" , "), "");
+        }
+    }
+
+    mod is_identifier {
+        use super::*;
+
+        #[test]
+        fn checking_value() {
+            assert!(is_identifier("Arial"));
+            assert!(is_identifier("-webkit-body"));
+            assert!(is_identifier("游ゴシック"));
+            assert!(!is_identifier("2000"));
+            assert!(!is_identifier("--"));
+            assert!(!is_identifier("M+"));
+            assert!(!is_identifier(""));
+        }
+    }
+}